@@ -0,0 +1,172 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use glam::Mat4;
+use glam::Vec3;
+
+use crate::BlendMode;
+use crate::EQFilesError;
+use crate::Model;
+use crate::ModelMaterial;
+use crate::ModelTriangle;
+use crate::Terrain;
+use crate::WldFile;
+use crate::WldMesh;
+use crate::WldModel;
+use crate::ZoneDefinition;
+
+/// The solid geometry of a zone in world space, for line of sight and height queries.
+/// Faces players walk through and those of invisible materials are left out.
+#[derive(Clone, Debug, Default)]
+pub struct CollisionMesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl CollisionMesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the solid faces of every mesh in `wld`.
+    pub fn add_wld(&mut self, wld: &WldFile) {
+        for mesh in wld.meshes() {
+            self.add_wld_mesh(wld, &mesh, None);
+        }
+    }
+
+    /// Adds `mesh`, optionally moved by `transform`. Materials are looked up in `wld`.
+    pub fn add_wld_mesh(&mut self, wld: &WldFile, mesh: &WldMesh, transform: Option<Mat4>) {
+        let invisible = wld
+            .resolve_material_list(mesh.material_list_ref)
+            .iter()
+            .map(|material| material.blend_mode == BlendMode::Invisible)
+            .collect::<Vec<_>>();
+        let mut triangles = Vec::new();
+        for (material, faces) in mesh.material_triangles() {
+            if material.and_then(|material| invisible.get(material)) == Some(&true) {
+                continue;
+            }
+            for [flags, a, b, c] in faces {
+                if flags & WldMesh::PASSABLE == 0 {
+                    triangles.push([*a as u32, *b as u32, *c as u32]);
+                }
+            }
+        }
+
+        let centre = Vec3::new(mesh.centre.0, mesh.centre.1, mesh.centre.2);
+        let transform = transform.unwrap_or(Mat4::IDENTITY) * Mat4::from_translation(centre);
+        self.add(&mesh.position, triangles, transform);
+    }
+
+    /// Adds the objects placed in `objects`, a zone's `objects.wld`, using the models
+    /// defined in `models`, usually its `<zone>_obj.wld`.
+    pub fn add_wld_objects(&mut self, objects: &WldFile, models: &WldFile) {
+        for location in objects.object_locations() {
+            let Some(model) = location
+                .model_name
+                .clone()
+                .and_then(|name| models.fragment_by_name::<WldModel>(name))
+            else {
+                continue;
+            };
+            for mesh in models.model_meshes(&model) {
+                self.add_wld_mesh(models, &mesh, Some(location.transform()));
+            }
+        }
+    }
+
+    /// Adds an EQG terrain, optionally moved by `transform`.
+    pub fn add_terrain(&mut self, terrain: &Terrain, transform: Option<Mat4>) {
+        self.add(
+            &terrain.vertices,
+            solid(&terrain.triangles, |triangle| terrain.material(triangle)),
+            transform.unwrap_or(Mat4::IDENTITY),
+        );
+    }
+
+    /// Adds an EQG model, optionally moved by `transform`, e.g. a [crate::ZoneObject]'s.
+    pub fn add_model(&mut self, model: &Model, transform: Option<Mat4>) {
+        self.add(
+            &model.vertices,
+            solid(&model.triangles, |triangle| model.material(triangle)),
+            transform.unwrap_or(Mat4::IDENTITY),
+        );
+    }
+
+    /// Adds every object of an EQG zone. `files` returns a file of the zone's archive by
+    /// name, for the `.ter` and `.mod` files it references.
+    pub fn add_zone(
+        &mut self,
+        zone: &ZoneDefinition,
+        files: impl Fn(&str) -> Option<Bytes>,
+    ) -> Result<(), EQFilesError> {
+        // zones place the same model many times, parse each file only once
+        let mut parsed = HashMap::new();
+        for object in &zone.objects {
+            let Some(filename) = &object.model else {
+                continue;
+            };
+            let geometry = match parsed.entry(filename.to_lowercase()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let mut contents = files(filename)
+                        .ok_or_else(|| EQFilesError::MissingFile(filename.clone()))?;
+                    let geometry = if entry.key().ends_with(".ter") {
+                        ZoneGeometry::Terrain(Terrain::parse(&mut contents)?)
+                    } else {
+                        ZoneGeometry::Model(Model::parse(&mut contents)?)
+                    };
+                    entry.insert(geometry)
+                }
+            };
+            match geometry {
+                ZoneGeometry::Terrain(terrain) => {
+                    self.add_terrain(terrain, Some(object.transform()))
+                }
+                ZoneGeometry::Model(model) => self.add_model(model, Some(object.transform())),
+            }
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, positions: &[[f32; 3]], triangles: Vec<[u32; 3]>, transform: Mat4) {
+        let offset = self.vertices.len() as u32;
+        self.vertices.extend(
+            positions
+                .iter()
+                .map(|position| transform.transform_point3(Vec3::from(*position)).to_array()),
+        );
+        self.triangles.extend(
+            triangles
+                .into_iter()
+                .filter(|triangle| {
+                    triangle
+                        .iter()
+                        .all(|index| (*index as usize) < positions.len())
+                })
+                .map(|triangle| triangle.map(|index| index + offset)),
+        );
+    }
+}
+
+/// A `.ter` or `.mod` file placed by a zone.
+enum ZoneGeometry {
+    Terrain(Terrain),
+    Model(Model),
+}
+
+/// EQG faces that are neither passable nor invisible, by flag or by material, the same
+/// faces that are left out of WLD meshes.
+fn solid<'a>(
+    triangles: &[ModelTriangle],
+    material: impl Fn(&ModelTriangle) -> Option<&'a ModelMaterial>,
+) -> Vec<[u32; 3]> {
+    triangles
+        .iter()
+        .filter(|triangle| !triangle.flags.passable() && !triangle.flags.invisible())
+        .filter(|triangle| !material(triangle).is_some_and(ModelMaterial::is_invisible))
+        .map(|triangle| triangle.vertices)
+        .collect()
+}
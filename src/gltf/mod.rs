@@ -0,0 +1,412 @@
+mod skin;
+
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use serde_json::Value;
+
+pub use self::skin::skinned_model_to_glb;
+use crate::utils::to_y_up;
+use crate::BlendMode;
+use crate::WldFile;
+use crate::WldMaterial;
+use crate::WldMaterialList;
+use crate::WldMesh;
+
+const GLB_MAGIC: u32 = 0x46546C67;
+const GLB_JSON_CHUNK: u32 = 0x4E4F534A;
+const GLB_BIN_CHUNK: u32 = 0x004E4942;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_BYTE: u32 = 5121;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+
+/// Exports `meshes` to a binary glTF, with their materials. `textures` is called with the
+/// filename of every texture, e.g. `sand.bmp`, and should return it encoded as PNG.
+pub fn meshes_to_glb(
+    wld: &WldFile,
+    meshes: &[WldMesh],
+    textures: impl Fn(&str) -> Option<Vec<u8>>,
+) -> Vec<u8> {
+    let mut builder = GltfBuilder::new(wld, textures);
+    let nodes = meshes
+        .iter()
+        .filter_map(|mesh| {
+            let index = builder.push_mesh(mesh, &mesh.position, &mesh.normal, None)?;
+            Some(builder.push_node(json!({ "name": mesh.name, "mesh": index })))
+        })
+        .collect::<Vec<_>>();
+    builder.scene(nodes);
+    builder.to_glb()
+}
+
+pub(crate) struct GltfBuilder<'a, F> {
+    wld: &'a WldFile,
+    textures: F,
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    meshes: Vec<Value>,
+    nodes: Vec<Value>,
+    materials: Vec<Value>,
+    material_by_ref: BTreeMap<u32, Option<usize>>,
+    images: Vec<Value>,
+    image_by_name: BTreeMap<String, Option<usize>>,
+    skins: Vec<Value>,
+    animations: Vec<Value>,
+    scene: Vec<usize>,
+}
+
+impl<'a, F> GltfBuilder<'a, F>
+where
+    F: Fn(&str) -> Option<Vec<u8>>,
+{
+    pub(crate) fn new(wld: &'a WldFile, textures: F) -> Self {
+        Self {
+            wld,
+            textures,
+            buffer: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            meshes: Vec::new(),
+            nodes: Vec::new(),
+            materials: Vec::new(),
+            material_by_ref: BTreeMap::new(),
+            images: Vec::new(),
+            image_by_name: BTreeMap::new(),
+            skins: Vec::new(),
+            animations: Vec::new(),
+            scene: Vec::new(),
+        }
+    }
+
+    fn push_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+        while !self.buffer.len().is_multiple_of(4) {
+            self.buffer.push(0);
+        }
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": data.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.buffer.extend_from_slice(data);
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+
+    pub(crate) fn push_floats<const N: usize>(
+        &mut self,
+        values: &[[f32; N]],
+        bounds: bool,
+    ) -> usize {
+        let data = values
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        let view = self.push_view(&data, Some(ARRAY_BUFFER));
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": accessor_type(N),
+        });
+        if bounds && !values.is_empty() {
+            let mut min = [f32::MAX; N];
+            let mut max = [f32::MIN; N];
+            for value in values {
+                for i in 0..N {
+                    min[i] = min[i].min(value[i]);
+                    max[i] = max[i].max(value[i]);
+                }
+            }
+            accessor["min"] = json!(min.to_vec());
+            accessor["max"] = json!(max.to_vec());
+        }
+        self.push_accessor(accessor)
+    }
+
+    fn push_accessor(&mut self, accessor: Value) -> usize {
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn push_colors(&mut self, colors: &[[u8; 4]]) -> usize {
+        // stored as BGRA
+        let data = colors
+            .iter()
+            .flat_map(|[b, g, r, a]| [*r, *g, *b, *a])
+            .collect::<Vec<_>>();
+        let view = self.push_view(&data, Some(ARRAY_BUFFER));
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_BYTE,
+            "normalized": true,
+            "count": colors.len(),
+            "type": "VEC4",
+        }))
+    }
+
+    fn push_joints(&mut self, joints: &[[u16; 4]]) -> usize {
+        let data = joints
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        let view = self.push_view(&data, Some(ARRAY_BUFFER));
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_SHORT,
+            "count": joints.len(),
+            "type": "VEC4",
+        }))
+    }
+
+    fn push_indices(&mut self, indices: &[u32]) -> usize {
+        let data = indices
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        let view = self.push_view(&data, Some(ELEMENT_ARRAY_BUFFER));
+        self.push_accessor(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }))
+    }
+
+    /// Adds `mesh` using `positions` and `normals` in EQ space, which may differ from the ones
+    /// in the mesh when it was skinned. `joints` holds the joint of every vertex when the mesh
+    /// is bound to a skin. Returns the glTF mesh index, or `None` when the mesh has no triangles.
+    pub(crate) fn push_mesh(
+        &mut self,
+        mesh: &WldMesh,
+        positions: &[[f32; 3]],
+        normals: &[[f32; 3]],
+        joints: Option<&[u16]>,
+    ) -> Option<usize> {
+        let vertex_count = positions.len();
+        if vertex_count == 0 || mesh.triangle.is_empty() {
+            return None;
+        }
+        let positions = positions
+            .iter()
+            .map(|p| {
+                to_y_up([
+                    p[0] + mesh.centre.0,
+                    p[1] + mesh.centre.1,
+                    p[2] + mesh.centre.2,
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let mut attributes = json!({ "POSITION": self.push_floats(&positions, true) });
+        if normals.len() == vertex_count {
+            let normals = normals.iter().map(|n| to_y_up(*n)).collect::<Vec<_>>();
+            attributes["NORMAL"] = json!(self.push_floats(&normals, false));
+        }
+        if mesh.uv.len() == vertex_count {
+            attributes["TEXCOORD_0"] = json!(self.push_floats(&mesh.uv, false));
+        }
+        if mesh.color.len() == vertex_count {
+            attributes["COLOR_0"] = json!(self.push_colors(&mesh.color));
+        }
+        if let Some(joints) = joints {
+            let joints = joints
+                .iter()
+                .map(|joint| [*joint, 0, 0, 0])
+                .collect::<Vec<_>>();
+            let weights = vec![[1.0f32, 0.0, 0.0, 0.0]; joints.len()];
+            attributes["JOINTS_0"] = json!(self.push_joints(&joints));
+            attributes["WEIGHTS_0"] = json!(self.push_floats(&weights, false));
+        }
+
+        let material_refs = self
+            .wld
+            .fragment_of_type::<WldMaterialList>(mesh.material_list_ref)
+            .map(|list| list.material_refs)
+            .unwrap_or_default();
+
+        let mut primitives = Vec::new();
+        for (material, triangles) in mesh.material_triangles() {
+            let indices = triangles
+                .iter()
+                .flat_map(|[_flags, a, b, c]| [*a as u32, *b as u32, *c as u32])
+                .collect::<Vec<_>>();
+            if indices.is_empty() {
+                continue;
+            }
+            let material = match material.and_then(|material| material_refs.get(material)) {
+                Some(material_ref) => match self.material(*material_ref) {
+                    Some(Some(material)) => Some(material),
+                    // invisible, e.g. zone boundaries
+                    Some(None) => continue,
+                    None => None,
+                },
+                None => None,
+            };
+            let mut primitive = json!({
+                "attributes": attributes.clone(),
+                "indices": self.push_indices(&indices),
+            });
+            if let Some(material) = material {
+                primitive["material"] = json!(material);
+            }
+            primitives.push(primitive);
+        }
+
+        if primitives.is_empty() {
+            return None;
+        }
+        self.meshes.push(json!({
+            "name": mesh.name,
+            "primitives": primitives,
+        }));
+        Some(self.meshes.len() - 1)
+    }
+
+    pub(crate) fn push_node(&mut self, node: Value) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    pub(crate) fn push_skin(&mut self, skin: Value) -> usize {
+        self.skins.push(skin);
+        self.skins.len() - 1
+    }
+
+    pub(crate) fn push_animation(&mut self, animation: Value) {
+        self.animations.push(animation);
+    }
+
+    pub(crate) fn scene(&mut self, nodes: Vec<usize>) {
+        self.scene.extend(nodes);
+    }
+
+    /// glTF index of the material at `material_ref`, or `Some(None)` if it is never drawn.
+    fn material(&mut self, material_ref: u32) -> Option<Option<usize>> {
+        if let Some(index) = self.material_by_ref.get(&material_ref) {
+            return Some(*index);
+        }
+        let material = self.wld.fragment_of_type::<WldMaterial>(material_ref)?;
+        let resolved = self.wld.resolve_material(&material);
+
+        let index = match resolved.blend_mode {
+            BlendMode::Invisible => None,
+            blend_mode => {
+                let mut value = json!({
+                    "name": resolved.name,
+                    "pbrMetallicRoughness": { "metallicFactor": 0.0 },
+                });
+                match blend_mode {
+                    BlendMode::Masked => value["alphaMode"] = json!("MASK"),
+                    BlendMode::Additive => value["alphaMode"] = json!("BLEND"),
+                    BlendMode::Transparent => {
+                        value["alphaMode"] = json!("BLEND");
+                        value["pbrMetallicRoughness"]["baseColorFactor"] =
+                            json!([1.0, 1.0, 1.0, resolved.opacity]);
+                    }
+                    _ => {}
+                }
+                if let Some(image) = resolved
+                    .textures
+                    .first()
+                    .and_then(|filename| self.image(filename))
+                {
+                    value["pbrMetallicRoughness"]["baseColorTexture"] = json!({ "index": image });
+                }
+                self.materials.push(value);
+                Some(self.materials.len() - 1)
+            }
+        };
+        self.material_by_ref.insert(material_ref, index);
+        Some(index)
+    }
+
+    /// Images and textures are kept one to one, so the image index is also the texture index.
+    fn image(&mut self, filename: &str) -> Option<usize> {
+        let key = filename.to_lowercase();
+        if let Some(index) = self.image_by_name.get(&key) {
+            return *index;
+        }
+        let index = (self.textures)(filename).map(|png| {
+            let view = self.push_view(&png, None);
+            self.images.push(json!({
+                "name": filename,
+                "bufferView": view,
+                "mimeType": "image/png",
+            }));
+            self.images.len() - 1
+        });
+        self.image_by_name.insert(key, index);
+        index
+    }
+
+    pub(crate) fn to_glb(&self) -> Vec<u8> {
+        let mut root = json!({
+            "asset": { "version": "2.0", "generator": "eq_files" },
+            "scene": 0,
+            "scenes": [{ "nodes": self.scene }],
+            "nodes": self.nodes,
+            "meshes": self.meshes,
+            "accessors": self.accessors,
+            "bufferViews": self.buffer_views,
+            "buffers": [{ "byteLength": self.buffer.len() }],
+        });
+        if !self.materials.is_empty() {
+            root["materials"] = json!(self.materials);
+        }
+        if !self.skins.is_empty() {
+            root["skins"] = json!(self.skins);
+        }
+        if !self.animations.is_empty() {
+            root["animations"] = json!(self.animations);
+        }
+        if !self.images.is_empty() {
+            root["images"] = json!(self.images);
+            root["samplers"] = json!([{}]);
+            root["textures"] = json!((0..self.images.len())
+                .map(|source| json!({ "sampler": 0, "source": source }))
+                .collect::<Vec<_>>());
+        }
+
+        let mut json = serde_json::to_vec(&root).expect("glTF document is valid JSON");
+        while !json.len().is_multiple_of(4) {
+            json.push(b' ');
+        }
+        let mut bin = self.buffer.clone();
+        while !bin.len().is_multiple_of(4) {
+            bin.push(0);
+        }
+
+        let mut result = Vec::with_capacity(12 + 8 + json.len() + 8 + bin.len());
+        result.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        result.extend_from_slice(&2u32.to_le_bytes());
+        result.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+        result.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        result.extend_from_slice(&GLB_JSON_CHUNK.to_le_bytes());
+        result.extend_from_slice(&json);
+        result.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        result.extend_from_slice(&GLB_BIN_CHUNK.to_le_bytes());
+        result.extend_from_slice(&bin);
+        result
+    }
+}
+
+fn accessor_type(components: usize) -> &'static str {
+    match components {
+        1 => "SCALAR",
+        2 => "VEC2",
+        3 => "VEC3",
+        4 => "VEC4",
+        16 => "MAT4",
+        _ => unreachable!("unsupported accessor size {}", components),
+    }
+}
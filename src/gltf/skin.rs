@@ -0,0 +1,172 @@
+use glam::Mat4;
+use glam::Quat;
+use glam::Vec4;
+use serde_json::json;
+
+use super::GltfBuilder;
+use crate::utils::to_y_up;
+use crate::Skeleton;
+use crate::SkeletonAnimation;
+use crate::SkeletonTrack;
+use crate::WldFile;
+use crate::WldMesh;
+
+/// Swaps Y and Z like [to_y_up]. It is its own inverse.
+const CONVERT: Mat4 = Mat4::from_cols(Vec4::X, Vec4::Z, Vec4::Y, Vec4::W);
+
+fn convert_matrix(matrix: Mat4) -> Mat4 {
+    CONVERT * matrix * CONVERT
+}
+
+/// Mirroring a rotation moves its axis and reverses its direction.
+fn convert_rotation(rotation: Quat) -> [f32; 4] {
+    [-rotation.x, -rotation.z, -rotation.y, rotation.w]
+}
+
+/// Exports a character to a binary glTF: `meshes` bound to a skin built from the dags of
+/// `skeleton`, and every one of `animations` as a glTF animation. `textures` behaves as in
+/// [super::meshes_to_glb].
+pub fn skinned_model_to_glb(
+    wld: &WldFile,
+    skeleton: &Skeleton,
+    meshes: &[WldMesh],
+    animations: &[SkeletonAnimation],
+    textures: impl Fn(&str) -> Option<Vec<u8>>,
+) -> Vec<u8> {
+    let mut builder = GltfBuilder::new(wld, textures);
+    let rest = skeleton.rest_pose();
+
+    // joints are pushed first, so the node index of a dag is its own index
+    for (index, dag) in skeleton.dags.iter().enumerate() {
+        let local = match dag.parent {
+            Some(parent) => rest[parent as usize].inverse() * rest[index],
+            None => rest[index],
+        };
+        let (_, rotation, translation) = convert_matrix(local).to_scale_rotation_translation();
+        let mut node = json!({
+            "name": dag.name,
+            "translation": translation.to_array(),
+            "rotation": rotation.normalize().to_array(),
+        });
+        if !dag.sub_dags.is_empty() {
+            node["children"] = json!(dag.sub_dags);
+        }
+        builder.push_node(node);
+    }
+    let roots = (0..skeleton.dags.len())
+        .filter(|index| skeleton.dags[*index].parent.is_none())
+        .collect::<Vec<_>>();
+
+    let inverse_bind_matrices = rest
+        .iter()
+        .map(|matrix| convert_matrix(*matrix).inverse().to_cols_array())
+        .collect::<Vec<_>>();
+    let inverse_bind_matrices = builder.push_floats(&inverse_bind_matrices, false);
+    let skin = builder.push_skin(json!({
+        "name": skeleton.name,
+        "joints": (0..skeleton.dags.len()).collect::<Vec<_>>(),
+        "inverseBindMatrices": inverse_bind_matrices,
+        "skeleton": roots.first(),
+    }));
+
+    let mut scene = roots;
+    for mesh in meshes {
+        let bind = mesh.skin(&rest);
+        let mut joints = vec![0u16; bind.position.len()];
+        for (start, count, dag) in &mesh.vertex_bone_group {
+            for joint in joints
+                .iter_mut()
+                .skip(*start as usize)
+                .take(*count as usize)
+            {
+                *joint = *dag;
+            }
+        }
+        if let Some(index) = builder.push_mesh(mesh, &bind.position, &bind.normal, Some(&joints)) {
+            scene.push(builder.push_node(json!({
+                "name": mesh.name,
+                "mesh": index,
+                "skin": skin,
+            })));
+        }
+    }
+    builder.scene(scene);
+
+    for animation in animations {
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        for (index, track) in &animation.tracks {
+            let Some(keyframes) = Keyframes::new(track) else {
+                continue;
+            };
+            let interpolation = if track.track.flags.interpolate() {
+                "LINEAR"
+            } else {
+                "STEP"
+            };
+            let input = builder.push_floats(&keyframes.times, true);
+            for (path, output) in [
+                (
+                    "translation",
+                    builder.push_floats(&keyframes.translations, false),
+                ),
+                ("rotation", builder.push_floats(&keyframes.rotations, false)),
+            ] {
+                channels.push(json!({
+                    "sampler": samplers.len(),
+                    "target": { "node": index, "path": path },
+                }));
+                samplers.push(json!({
+                    "input": input,
+                    "output": output,
+                    "interpolation": interpolation,
+                }));
+            }
+        }
+        if !channels.is_empty() {
+            builder.push_animation(json!({
+                "name": format!("{}{}", animation.code, animation.model),
+                "samplers": samplers,
+                "channels": channels,
+            }));
+        }
+    }
+
+    builder.to_glb()
+}
+
+/// Every frame of a track in glTF space, with times in seconds.
+struct Keyframes {
+    times: Vec<[f32; 1]>,
+    translations: Vec<[f32; 3]>,
+    rotations: Vec<[f32; 4]>,
+}
+
+impl Keyframes {
+    fn new(track: &SkeletonTrack) -> Option<Self> {
+        let mut frames = track.definition.frames.clone();
+        if frames.is_empty() {
+            return None;
+        }
+        if track.track.flags.reverse() {
+            frames.reverse();
+        }
+        // without a sleep the track never advances past its first frame
+        if track.sleep().is_none() {
+            frames.truncate(1);
+        }
+        let sleep = track.sleep().unwrap_or(0) as f32 / 1000.0;
+
+        Some(Self {
+            times: (0..frames.len()).map(|i| [i as f32 * sleep]).collect(),
+            translations: frames
+                .iter()
+                .map(|frame| to_y_up(frame.translation.to_array()))
+                .collect(),
+            rotations: frames
+                .iter()
+                .map(|frame| convert_rotation(frame.rotation))
+                .collect(),
+        })
+    }
+}
@@ -1,7 +1,15 @@
+mod collision;
+pub mod gltf;
+mod map;
 mod model;
+pub mod obj;
 mod pfs;
+mod sound;
+mod text;
+mod texture;
 mod utils;
 mod wld;
+mod zone;
 
 use std::string::FromUtf8Error;
 use std::sync::Arc;
@@ -10,10 +18,58 @@ use bytes::Bytes;
 use lazy_static::lazy_static;
 use thiserror::Error;
 
+pub use crate::collision::CollisionMesh;
+pub use crate::map::MapFile;
+pub use crate::map::MapGenerator;
+pub use crate::map::MapLine;
+pub use crate::map::MapPoint;
+pub use crate::model::Animation;
+pub use crate::model::AnimationKeyframe;
+pub use crate::model::AnimationTrack;
+pub use crate::model::MaterialLayer;
+pub use crate::model::MaterialLayers;
 pub use crate::model::Model;
+pub use crate::model::ModelBone;
+pub use crate::model::ModelMaterial;
+pub use crate::model::ModelMaterialPropertyValue;
+pub use crate::model::ModelSkinnedMesh;
+pub use crate::model::ModelTriangle;
+pub use crate::model::ModelTriangleFlags;
+pub use crate::model::ModelVertexWeights;
+pub use crate::model::ParticleDefinition;
+pub use crate::model::ParticleDefinitions;
+pub use crate::model::ParticlePoint;
+pub use crate::model::ParticlePoints;
+pub use crate::model::Terrain;
+pub use crate::model::ZoneDefinition;
+pub use crate::model::ZoneLight;
+pub use crate::model::ZoneObject;
+pub use crate::model::ZoneRegion;
 pub use crate::pfs::PackFile;
+pub use crate::pfs::PackFileWriter;
+pub use crate::sound::EmitterFile;
+pub use crate::sound::EmitterRecord;
+pub use crate::sound::SoundBank;
+pub use crate::sound::SoundEffectRecord;
+pub use crate::sound::SoundEffects;
+pub use crate::sound::SoundEmitter;
+pub use crate::sound::SoundEmitterKind;
+pub use crate::text::DbStringTable;
+pub use crate::text::Spell;
+pub use crate::text::SpellDatabase;
+pub use crate::text::StringTable;
+pub use crate::texture::DxtFormat;
+pub use crate::texture::Texture;
 pub use crate::wld::fragments::*;
+pub use crate::wld::material::BlendMode;
+pub use crate::wld::material::ResolvedMaterial;
+pub use crate::wld::skeleton::Skeleton;
+pub use crate::wld::skeleton::SkeletonAnimation;
+pub use crate::wld::skeleton::SkeletonTrack;
 pub use crate::wld::WldFile;
+pub use crate::zone::CharacterImport;
+pub use crate::zone::CharacterImports;
+pub use crate::zone::LitFile;
 
 #[derive(Debug, Error)]
 pub enum EQFilesError {
@@ -25,6 +81,24 @@ pub enum EQFilesError {
     InvalidVersionNumber(u32),
     #[error("error decoding string")]
     ErrorDecodingString(#[from] FromUtf8Error),
+    #[error("invalid texture: {0}")]
+    InvalidTexture(&'static str),
+    #[error("unknown material property type {0}")]
+    UnknownMaterialPropertyType(u32),
+    #[error("invalid line {0}: {1}")]
+    InvalidTextLine(usize, &'static str),
+    #[error("invalid sound file: {0}")]
+    InvalidSoundFile(&'static str),
+    #[error("lit file too short for {0} colors")]
+    InvalidLitFile(usize),
+    #[error("file {0} not found")]
+    MissingFile(String),
+    #[error("error reading file")]
+    Io(#[from] std::io::Error),
+    #[error("unsupported {0} version {1}")]
+    UnsupportedVersion(&'static str, u32),
+    #[error("truncated {0}")]
+    Truncated(&'static str),
 }
 
 #[derive(Default)]
@@ -0,0 +1,126 @@
+use glam::Vec3;
+
+use super::MapFile;
+use super::MapLine;
+use crate::BlendMode;
+use crate::ModelMaterial;
+use crate::Terrain;
+use crate::WldFile;
+use crate::WldMesh;
+
+/// Derives map lines from zone geometry by cutting it with horizontal planes, which traces
+/// the walls at each of the chosen heights.
+#[derive(Clone, Debug)]
+pub struct MapGenerator {
+    /// World Z of every slice.
+    pub heights: Vec<f32>,
+    pub color: [u8; 3],
+    lines: Vec<MapLine>,
+}
+
+impl MapGenerator {
+    pub fn new(heights: Vec<f32>) -> Self {
+        Self {
+            heights,
+            color: [0, 0, 0],
+            lines: Vec::new(),
+        }
+    }
+
+    /// Slices every mesh of `wld`.
+    pub fn add_wld(&mut self, wld: &WldFile) {
+        for mesh in wld.meshes() {
+            self.add_wld_mesh(wld, &mesh);
+        }
+    }
+
+    /// Slices `mesh`, leaving out the faces players walk through and those with invisible
+    /// materials. Materials are looked up in `wld`.
+    pub fn add_wld_mesh(&mut self, wld: &WldFile, mesh: &WldMesh) {
+        let invisible = wld
+            .resolve_material_list(mesh.material_list_ref)
+            .iter()
+            .map(|material| material.blend_mode == BlendMode::Invisible)
+            .collect::<Vec<_>>();
+        let centre = Vec3::new(mesh.centre.0, mesh.centre.1, mesh.centre.2);
+        let vertex = |index: &u16| {
+            mesh.position
+                .get(*index as usize)
+                .map(|position| Vec3::from(*position) + centre)
+        };
+        for (material, triangles) in mesh.material_triangles() {
+            if material.and_then(|material| invisible.get(material)) == Some(&true) {
+                continue;
+            }
+            for [flags, a, b, c] in triangles {
+                if flags & WldMesh::PASSABLE != 0 {
+                    continue;
+                }
+                if let (Some(a), Some(b), Some(c)) = (vertex(a), vertex(b), vertex(c)) {
+                    self.add_triangle([a, b, c]);
+                }
+            }
+        }
+    }
+
+    /// Slices an EQG terrain, leaving out the faces players walk through and invisible ones.
+    pub fn add_terrain(&mut self, terrain: &Terrain) {
+        for triangle in &terrain.triangles {
+            if triangle.flags.passable()
+                || triangle.flags.invisible()
+                || terrain
+                    .material(triangle)
+                    .is_some_and(ModelMaterial::is_invisible)
+            {
+                continue;
+            }
+            let vertex = |index: u32| {
+                terrain
+                    .vertices
+                    .get(index as usize)
+                    .copied()
+                    .map(Vec3::from)
+            };
+            let [a, b, c] = triangle.vertices;
+            if let (Some(a), Some(b), Some(c)) = (vertex(a), vertex(b), vertex(c)) {
+                self.add_triangle([a, b, c]);
+            }
+        }
+    }
+
+    /// Adds the segments where a triangle, in world space, crosses the slice heights.
+    pub fn add_triangle(&mut self, vertices: [Vec3; 3]) {
+        for height in &self.heights {
+            let mut crossings = Vec::with_capacity(2);
+            for (start, end) in [(0, 1), (1, 2), (2, 0)] {
+                let (start, end) = (vertices[start], vertices[end]);
+                // a vertex exactly on the plane counts as above it, so it's only crossed once
+                if (start.z >= *height) != (end.z >= *height) {
+                    let t = (height - start.z) / (end.z - start.z);
+                    crossings.push(start.lerp(end, t));
+                }
+            }
+            if let [start, end] = crossings[..] {
+                if start.distance_squared(end) > f32::EPSILON {
+                    self.lines.push(MapLine {
+                        start: to_map(start),
+                        end: to_map(end),
+                        color: self.color,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> MapFile {
+        MapFile {
+            lines: self.lines,
+            points: Vec::new(),
+        }
+    }
+}
+
+fn to_map(position: Vec3) -> Vec3 {
+    // adding zero turns -0 into 0, which would otherwise be written as -0.0000
+    Vec3::new(-position.x + 0.0, -position.y + 0.0, position.z)
+}
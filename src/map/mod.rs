@@ -0,0 +1,165 @@
+mod generator;
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use glam::Vec3;
+
+pub use self::generator::MapGenerator;
+use crate::text::decode;
+use crate::EQFilesError;
+
+/// A Brewall style map overlay (`maps/<zone>_1.txt`). Coordinates are as in the file, which
+/// has X and Y negated compared to the world.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MapFile {
+    pub lines: Vec<MapLine>,
+    pub points: Vec<MapPoint>,
+}
+
+/// `L x1, y1, z1, x2, y2, z2, r, g, b`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapLine {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub color: [u8; 3],
+}
+
+/// `P x, y, z, r, g, b, size, label`, with the label's spaces written as underscores.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapPoint {
+    pub position: Vec3,
+    pub color: [u8; 3],
+    pub size: u32,
+    pub label: String,
+}
+
+impl MapFile {
+    pub fn parse(bytes: &[u8]) -> Result<Self, EQFilesError> {
+        let text = decode(bytes);
+        let mut result = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let error = |reason| EQFilesError::InvalidTextLine(number + 1, reason);
+            let line = line.trim();
+            let Some((kind, rest)) = line.split_once(char::is_whitespace) else {
+                if line.is_empty() {
+                    continue;
+                }
+                return Err(error("missing map record fields"));
+            };
+            match kind {
+                "L" => {
+                    let fields = rest.splitn(9, ',').map(str::trim).collect::<Vec<_>>();
+                    let values = numbers(&fields, 9).ok_or_else(|| error("invalid map line"))?;
+                    result.lines.push(MapLine {
+                        start: Vec3::new(values[0], values[1], values[2]),
+                        end: Vec3::new(values[3], values[4], values[5]),
+                        color: color(&values[6..9]),
+                    });
+                }
+                "P" => {
+                    let fields = rest.splitn(8, ',').map(str::trim).collect::<Vec<_>>();
+                    let values = numbers(&fields, 7).ok_or_else(|| error("invalid map point"))?;
+                    result.points.push(MapPoint {
+                        position: Vec3::new(values[0], values[1], values[2]),
+                        color: color(&values[3..6]),
+                        size: values[6] as u32,
+                        label: fields.get(7).unwrap_or(&"").replace('_', " "),
+                    });
+                }
+                _ => return Err(error("unknown map record")),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The first `count` fields parsed as numbers.
+fn numbers(fields: &[&str], count: usize) -> Option<Vec<f32>> {
+    fields
+        .get(..count)?
+        .iter()
+        .map(|field| field.parse().ok())
+        .collect()
+}
+
+fn color(values: &[f32]) -> [u8; 3] {
+    [0, 1, 2].map(|i| values[i].clamp(0.0, 255.0) as u8)
+}
+
+impl Display for MapFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            writeln!(
+                f,
+                "L {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {:.4}, {}, {}, {}",
+                line.start.x,
+                line.start.y,
+                line.start.z,
+                line.end.x,
+                line.end.y,
+                line.end.z,
+                line.color[0],
+                line.color[1],
+                line.color[2],
+            )?;
+        }
+        for point in &self.points {
+            writeln!(
+                f,
+                "P {:.4}, {:.4}, {:.4}, {}, {}, {}, {}, {}",
+                point.position.x,
+                point.position.y,
+                point.position.z,
+                point.color[0],
+                point.color[1],
+                point.color[2],
+                point.size,
+                point.label.replace(' ', "_"),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_then_read_is_unchanged() {
+        let map = MapFile {
+            lines: vec![
+                MapLine {
+                    start: Vec3::new(-120.5, 33.25, 4.0),
+                    end: Vec3::new(80.0, -16.125, 4.0),
+                    color: [240, 0, 0],
+                },
+                MapLine {
+                    start: Vec3::ZERO,
+                    end: Vec3::new(1.0, 2.0, 3.0),
+                    color: [0, 0, 0],
+                },
+            ],
+            points: vec![MapPoint {
+                position: Vec3::new(10.0, -20.0, 0.5),
+                color: [127, 64, 0],
+                size: 3,
+                label: String::from("Zone in"),
+            }],
+        };
+        assert_eq!(MapFile::parse(map.to_string().as_bytes()).unwrap(), map);
+    }
+
+    #[test]
+    fn reads_brewall_records() {
+        let map = MapFile::parse(
+            b"L 1.0000, 2.0000, 3.0000, 4.0000, 5.0000, 6.0000, 7, 8, 9\r\n\r\n\
+              P -1.5, 2, 0, 255, 255, 255, 2, Guard_Tower\r\n",
+        )
+        .unwrap();
+        assert_eq!(map.lines[0].end, Vec3::new(4.0, 5.0, 6.0));
+        assert_eq!(map.lines[0].color, [7, 8, 9]);
+        assert_eq!(map.points[0].label, "Guard Tower");
+    }
+}
@@ -0,0 +1,161 @@
+use bytes::Buf;
+use bytes::Bytes;
+use glam::Mat4;
+use glam::Quat;
+use glam::Vec3;
+
+use super::Model;
+use super::ModelStringTable;
+use crate::utils::check_remaining;
+use crate::utils::read_vec3;
+use crate::EQFilesError;
+
+const HEADER_SIZE: usize = 16;
+/// Time, translation, rotation and scale.
+const KEYFRAME_SIZE: usize = 44;
+
+/// An EQG skeletal animation (`.ani`), with keyframes for bones matched to a [Model]'s
+/// by name.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub version: u32,
+    pub tracks: Vec<AnimationTrack>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnimationTrack {
+    pub bone: String,
+    /// Sorted by time.
+    pub frames: Vec<AnimationKeyframe>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AnimationKeyframe {
+    pub time_ms: u32,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Animation {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < HEADER_SIZE {
+            return Err(EQFilesError::Truncated("animation header"));
+        }
+        let magic = bytes.get_u32_le();
+        if magic != 0x41475145 {
+            return Err(EQFilesError::InvalidMagicNumber(magic));
+        }
+        let version = bytes.get_u32_le();
+        let strings_length = bytes.get_u32_le();
+        let bone_count = bytes.get_u32_le();
+        if version > 1 {
+            if bytes.remaining() < 4 {
+                return Err(EQFilesError::Truncated("animation header"));
+            }
+            let _unknown = bytes.get_u32_le();
+        }
+
+        let strings = ModelStringTable::parse(bytes, strings_length as usize)?;
+        let mut tracks = Vec::new();
+        for _ in 0..bone_count {
+            if bytes.remaining() < 8 {
+                return Err(EQFilesError::Truncated("animation track"));
+            }
+            let frame_count = bytes.get_u32_le();
+            let bone = strings.get_string(bytes.get_u32_le() as usize)?;
+            check_remaining(
+                bytes,
+                frame_count as usize,
+                KEYFRAME_SIZE,
+                "animation keyframes",
+            )?;
+            let mut frames = Vec::new();
+            for _ in 0..frame_count {
+                frames.push(AnimationKeyframe {
+                    time_ms: bytes.get_u32_le(),
+                    translation: read_vec3(bytes),
+                    rotation: Quat::from_xyzw(
+                        bytes.get_f32_le(),
+                        bytes.get_f32_le(),
+                        bytes.get_f32_le(),
+                        bytes.get_f32_le(),
+                    ),
+                    scale: read_vec3(bytes),
+                });
+            }
+            frames.sort_by_key(|frame| frame.time_ms);
+            tracks.push(AnimationTrack { bone, frames });
+        }
+
+        Ok(Self { version, tracks })
+    }
+
+    /// Time of the last keyframe.
+    pub fn duration_ms(&self) -> u32 {
+        self.tracks
+            .iter()
+            .filter_map(|track| track.frames.last())
+            .map(|frame| frame.time_ms)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn track(&self, bone: &str) -> Option<&AnimationTrack> {
+        self.tracks
+            .iter()
+            .find(|track| track.bone.eq_ignore_ascii_case(bone))
+    }
+
+    /// Model space matrix of every bone of `model` at `time_ms`, looping. Bones without a
+    /// track keep their bind pose, and the result can be passed to [Model::skin].
+    pub fn sample(&self, model: &Model, time_ms: u32) -> Vec<Mat4> {
+        let duration = self.duration_ms();
+        let time_ms = if duration > 0 { time_ms % duration } else { 0 };
+        let locals = model
+            .bones
+            .iter()
+            .map(|bone| {
+                self.track(&bone.name)
+                    .and_then(|track| track.sample(time_ms))
+                    .unwrap_or_else(|| bone.local_matrix())
+            })
+            .collect::<Vec<_>>();
+        model.pose(&locals)
+    }
+}
+
+impl AnimationTrack {
+    /// Transform relative to the parent bone at `time_ms`, interpolated between the
+    /// surrounding keyframes.
+    pub fn sample(&self, time_ms: u32) -> Option<Mat4> {
+        let next = self
+            .frames
+            .iter()
+            .position(|frame| frame.time_ms > time_ms)
+            .unwrap_or(self.frames.len());
+        let frame = match (next.checked_sub(1), self.frames.get(next)) {
+            (Some(previous), Some(next)) => {
+                let previous = &self.frames[previous];
+                let t =
+                    (time_ms - previous.time_ms) as f32 / (next.time_ms - previous.time_ms) as f32;
+                AnimationKeyframe {
+                    time_ms,
+                    translation: previous.translation.lerp(next.translation, t),
+                    rotation: previous
+                        .rotation
+                        .normalize()
+                        .slerp(next.rotation.normalize(), t),
+                    scale: previous.scale.lerp(next.scale, t),
+                }
+            }
+            (Some(previous), None) => self.frames[previous],
+            (None, _) => *self.frames.first()?,
+        };
+        Some(Mat4::from_scale_rotation_translation(
+            frame.scale,
+            frame.rotation.normalize(),
+            frame.translation,
+        ))
+    }
+}
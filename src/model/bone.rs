@@ -0,0 +1,81 @@
+use bytes::Buf;
+use bytes::Bytes;
+use glam::Mat4;
+use glam::Quat;
+use glam::Vec3;
+
+use super::ModelStringTable;
+use crate::EQFilesError;
+
+/// A bone of an EQG model. The hierarchy is stored as a first child and a next sibling.
+#[derive(Clone, Debug)]
+pub struct ModelBone {
+    pub name: String,
+    /// Next sibling.
+    pub link: Option<usize>,
+    pub flags: u32,
+    /// First child.
+    pub child: Option<usize>,
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl ModelBone {
+    pub(super) fn parse(
+        bytes: &mut Bytes,
+        strings: &ModelStringTable,
+    ) -> Result<Self, EQFilesError> {
+        let name = strings.get_string(bytes.get_u32_le() as usize)?;
+        let link = index(bytes.get_i32_le());
+        let flags = bytes.get_u32_le();
+        let child = index(bytes.get_i32_le());
+        let position = Vec3::new(bytes.get_f32_le(), bytes.get_f32_le(), bytes.get_f32_le());
+        let rotation = Quat::from_xyzw(
+            bytes.get_f32_le(),
+            bytes.get_f32_le(),
+            bytes.get_f32_le(),
+            bytes.get_f32_le(),
+        );
+        let scale = Vec3::new(bytes.get_f32_le(), bytes.get_f32_le(), bytes.get_f32_le());
+
+        Ok(Self {
+            name,
+            link,
+            flags,
+            child,
+            position,
+            rotation,
+            scale,
+        })
+    }
+
+    /// Transform relative to the parent bone.
+    pub fn local_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation.normalize(), self.position)
+    }
+}
+
+fn index(value: i32) -> Option<usize> {
+    (value >= 0).then_some(value as usize)
+}
+
+/// Up to four bones influencing a vertex.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ModelVertexWeights {
+    pub count: u32,
+    pub weights: [(u32, f32); 4],
+}
+
+impl ModelVertexWeights {
+    pub(super) fn parse(bytes: &mut Bytes) -> Self {
+        let count = bytes.get_u32_le();
+        let weights = std::array::from_fn(|_| (bytes.get_u32_le(), bytes.get_f32_le()));
+        Self { count, weights }
+    }
+
+    /// The `(bone, weight)` pairs in use.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, f32)> + '_ {
+        self.weights.iter().take(self.count as usize).copied()
+    }
+}
@@ -0,0 +1,76 @@
+use bytes::Buf;
+use bytes::Bytes;
+
+use super::Model;
+use super::ModelMaterial;
+use super::ModelStringTable;
+use crate::EQFilesError;
+
+const HEADER_SIZE: usize = 16;
+
+/// Texture variants of a model's materials (`.lay`), used for armor and faces.
+#[derive(Clone, Debug)]
+pub struct MaterialLayers {
+    pub version: u32,
+    pub layers: Vec<MaterialLayer>,
+}
+
+/// Replaces the textures of one material.
+#[derive(Clone, Debug)]
+pub struct MaterialLayer {
+    pub material: String,
+    pub diffuse: String,
+    pub normal: String,
+}
+
+impl MaterialLayers {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < HEADER_SIZE {
+            return Err(EQFilesError::Truncated("layer header"));
+        }
+        let magic = bytes.get_u32_le();
+        if magic != 0x4c475145 {
+            return Err(EQFilesError::InvalidMagicNumber(magic));
+        }
+        let version = bytes.get_u32_le();
+        let strings_length = bytes.get_u32_le();
+        let layer_count = bytes.get_u32_le() as usize;
+        let entry_size = entry_size(version)?;
+        let strings = ModelStringTable::parse(bytes, strings_length as usize)?;
+        if bytes.remaining() < layer_count.saturating_mul(entry_size) {
+            return Err(EQFilesError::Truncated("layer entries"));
+        }
+
+        let mut layers = Vec::with_capacity(layer_count);
+        for _ in 0..layer_count {
+            let mut entry = bytes.copy_to_bytes(entry_size);
+            layers.push(MaterialLayer {
+                material: strings.get_string(entry.get_u32_le() as usize)?,
+                diffuse: strings.get_string(entry.get_u32_le() as usize)?,
+                normal: strings.get_string(entry.get_u32_le() as usize)?,
+            });
+        }
+
+        Ok(Self { version, layers })
+    }
+}
+
+/// Every version starts its entries with the material, diffuse and normal map names; later
+/// ones follow them with 40 bytes we don't decode.
+fn entry_size(version: u32) -> Result<usize, EQFilesError> {
+    match version {
+        2 => Ok(12),
+        3 | 4 => Ok(52),
+        _ => Err(EQFilesError::UnsupportedVersion("layer", version)),
+    }
+}
+
+impl MaterialLayer {
+    /// The material of `model` this layer applies to.
+    pub fn target<'a>(&self, model: &'a Model) -> Option<&'a ModelMaterial> {
+        model
+            .materials
+            .iter()
+            .find(|material| material.material_name.eq_ignore_ascii_case(&self.material))
+    }
+}
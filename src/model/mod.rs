@@ -1,17 +1,158 @@
+mod animation;
+mod bone;
+mod layer;
+mod particle;
+mod terrain;
+mod zone;
+
 use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
 
+use bitbybit::bitfield;
 use bytes::Buf;
 use bytes::Bytes;
+use glam::Mat4;
+use glam::Vec3;
 
+pub use self::animation::Animation;
+pub use self::animation::AnimationKeyframe;
+pub use self::animation::AnimationTrack;
+pub use self::bone::ModelBone;
+pub use self::bone::ModelVertexWeights;
+pub use self::layer::MaterialLayer;
+pub use self::layer::MaterialLayers;
+pub use self::particle::ParticleDefinition;
+pub use self::particle::ParticleDefinitions;
+pub use self::particle::ParticlePoint;
+pub use self::particle::ParticlePoints;
+pub use self::terrain::Terrain;
+pub use self::zone::ZoneDefinition;
+pub use self::zone::ZoneLight;
+pub use self::zone::ZoneObject;
+pub use self::zone::ZoneRegion;
+use crate::utils::check_remaining;
 use crate::EQFilesError;
 
+/// An EQG model (`.mod`, and the `.mds` characters which share the format).
+#[derive(Clone, Debug)]
 pub struct Model {
-    version: u32,
-    materials: Vec<ModelMaterial>,
-    vertices: Vec<[f32; 3]>,
-    normals: Vec<[f32; 3]>,
-    uvs: Vec<[f32; 2]>,
-    triangles: Vec<(u32, u32, u32, u32, u32)>,
+    pub version: u32,
+    pub materials: Vec<ModelMaterial>,
+    pub vertices: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    /// Version 3 and later only, RGBA.
+    pub colors: Vec<[u8; 4]>,
+    /// Version 3 and later only.
+    pub secondary_uvs: Vec<[f32; 2]>,
+    pub triangles: Vec<ModelTriangle>,
+    pub bones: Vec<ModelBone>,
+    /// One per vertex when the model has bones.
+    pub weights: Vec<ModelVertexWeights>,
+}
+
+/// Vertex data of a [Model] after bone transforms were applied, like
+/// [crate::WldSkinnedMesh] for WLD meshes.
+#[derive(Clone, Debug)]
+pub struct ModelSkinnedMesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ModelTriangle {
+    pub vertices: [u32; 3],
+    /// Index of the entry of [Model::materials] used, or -1 for none.
+    pub material: i32,
+    pub flags: ModelTriangleFlags,
+}
+
+#[bitfield(u32)]
+pub struct ModelTriangleFlags {
+    /// Players walk through it.
+    #[bit(0, r)]
+    pub passable: bool, // 0x01
+    /// Isn't drawn.
+    #[bit(1, r)]
+    pub invisible: bool, // 0x02
+}
+
+impl Debug for ModelTriangleFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModelTriangleFlags")
+            .field("passable", &self.passable())
+            .field("invisible", &self.invisible())
+            .finish()
+    }
+}
+
+/// The vertex section shared by models and terrain, whose layout depends on the version.
+pub(crate) struct ModelVertices {
+    pub(crate) positions: Vec<[f32; 3]>,
+    pub(crate) normals: Vec<[f32; 3]>,
+    pub(crate) uvs: Vec<[f32; 2]>,
+    pub(crate) colors: Vec<[u8; 4]>,
+    pub(crate) secondary_uvs: Vec<[f32; 2]>,
+}
+
+impl ModelVertices {
+    pub(crate) fn parse(bytes: &mut Bytes, version: u32, count: u32) -> Result<Self, EQFilesError> {
+        // position, normal and uv, plus a colour and a second uv from version 3
+        let size = if version >= 3 { 44 } else { 32 };
+        check_remaining(bytes, count as usize, size, "vertices")?;
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut colors = Vec::new();
+        let mut secondary_uvs = Vec::new();
+        for _ in 0..count {
+            positions.push([bytes.get_f32_le(), bytes.get_f32_le(), bytes.get_f32_le()]);
+            normals.push([bytes.get_f32_le(), bytes.get_f32_le(), bytes.get_f32_le()]);
+            if version >= 3 {
+                let [b, g, r, a] = bytes.get_u32_le().to_le_bytes();
+                colors.push([r, g, b, a]);
+            }
+            let u = bytes.get_f32_le();
+            let v = bytes.get_f32_le();
+
+            uvs.push([v, u]);
+            if version >= 3 {
+                secondary_uvs.push([bytes.get_f32_le(), bytes.get_f32_le()]);
+            }
+        }
+        Ok(Self {
+            positions,
+            normals,
+            uvs,
+            colors,
+            secondary_uvs,
+        })
+    }
+}
+
+/// Materials are matched by their index field, not their position in the list.
+pub(crate) fn triangle_material<'a>(
+    materials: &'a [ModelMaterial],
+    triangle: &ModelTriangle,
+) -> Option<&'a ModelMaterial> {
+    materials
+        .iter()
+        .find(|material| material.index as i32 == triangle.material)
+}
+
+pub(crate) fn parse_triangles(
+    bytes: &mut Bytes,
+    count: u32,
+) -> Result<Vec<ModelTriangle>, EQFilesError> {
+    check_remaining(bytes, count as usize, 20, "triangles")?;
+    Ok((0..count)
+        .map(|_| ModelTriangle {
+            vertices: [bytes.get_u32_le(), bytes.get_u32_le(), bytes.get_u32_le()],
+            material: bytes.get_i32_le(),
+            flags: ModelTriangleFlags::new_with_raw_value(bytes.get_u32_le()),
+        })
+        .collect())
 }
 
 impl Model {
@@ -25,54 +166,158 @@ impl Model {
         let material_count = bytes.get_u32_le();
         let vertex_count = bytes.get_u32_le();
         let triangle_count = bytes.get_u32_le();
-        let _bone_count = bytes.get_u32_le();
+        let bone_count = bytes.get_u32_le();
 
         let strings = ModelStringTable::parse(bytes, strings_length as usize)?;
         let mut materials = Vec::new();
         for _ in 0..material_count {
             materials.push(ModelMaterial::parse(bytes, &strings)?);
         }
-        let mut vertices: Vec<[f32; 3]> = Vec::new();
-        let mut normals = Vec::new();
-        let mut uvs = Vec::new();
-        for _ in 0..vertex_count {
-            vertices.push([bytes.get_f32_le(), bytes.get_f32_le(), bytes.get_f32_le()]);
-            normals.push([bytes.get_f32_le(), bytes.get_f32_le(), bytes.get_f32_le()]);
-            let u = bytes.get_f32_le();
-            let v = bytes.get_f32_le();
+        let vertices = ModelVertices::parse(bytes, version, vertex_count)?;
+        let triangles = parse_triangles(bytes, triangle_count)?;
 
-            uvs.push([v, u]);
+        let mut bones = Vec::new();
+        for _ in 0..bone_count {
+            bones.push(ModelBone::parse(bytes, &strings)?);
         }
-        let mut triangles = Vec::new();
-        for _ in 0..triangle_count {
-            let a = bytes.get_u32_le();
-            let b = bytes.get_u32_le();
-            let c = bytes.get_u32_le();
-            let material_id = bytes.get_u32_le();
-            let flags = bytes.get_u32_le();
-            triangles.push((a, b, c, material_id, flags));
+        let mut weights = Vec::new();
+        if bone_count > 0 {
+            for _ in 0..vertex_count {
+                weights.push(ModelVertexWeights::parse(bytes));
+            }
         }
 
-        //TODO: check format on bones
-
         Ok(Self {
             version,
             materials,
-            vertices,
-            normals,
-            uvs,
+            vertices: vertices.positions,
+            normals: vertices.normals,
+            uvs: vertices.uvs,
+            colors: vertices.colors,
+            secondary_uvs: vertices.secondary_uvs,
             triangles,
+            bones,
+            weights,
         })
     }
+
+    /// The material a triangle uses.
+    pub fn material(&self, triangle: &ModelTriangle) -> Option<&ModelMaterial> {
+        triangle_material(&self.materials, triangle)
+    }
+
+    /// Parent of every bone, from the child and sibling links.
+    pub fn bone_parents(&self) -> Vec<Option<usize>> {
+        let mut parents = vec![None; self.bones.len()];
+        for (index, bone) in self.bones.iter().enumerate() {
+            let mut child = bone.child;
+            // the guard stops malformed links from looping forever
+            let mut guard = self.bones.len();
+            while let Some(current) = child.filter(|child| *child < self.bones.len()) {
+                if guard == 0 {
+                    break;
+                }
+                guard -= 1;
+                parents[current] = Some(index);
+                child = self.bones[current].link;
+            }
+        }
+        parents
+    }
+
+    /// Model space matrix of every bone in its bind pose.
+    pub fn bind_pose(&self) -> Vec<Mat4> {
+        let locals = self
+            .bones
+            .iter()
+            .map(ModelBone::local_matrix)
+            .collect::<Vec<_>>();
+        self.pose(&locals)
+    }
+
+    /// Model space matrices from local ones, one per bone.
+    pub fn pose(&self, locals: &[Mat4]) -> Vec<Mat4> {
+        let parents = self.bone_parents();
+        let mut result = vec![None; self.bones.len()];
+        for index in 0..self.bones.len() {
+            pose_bone(index, locals, &parents, &mut result);
+        }
+        result.into_iter().map(Option::unwrap_or_default).collect()
+    }
+
+    /// Vertices and normals deformed by `pose`, one model space matrix per bone as returned
+    /// by [Model::pose]. Models without weights are returned unchanged.
+    pub fn skin(&self, pose: &[Mat4]) -> ModelSkinnedMesh {
+        if self.weights.len() != self.vertices.len() {
+            return ModelSkinnedMesh {
+                vertices: self.vertices.clone(),
+                normals: self.normals.clone(),
+            };
+        }
+        let bind = self
+            .bind_pose()
+            .iter()
+            .map(Mat4::inverse)
+            .collect::<Vec<_>>();
+        let skinning = |weights: &ModelVertexWeights| {
+            weights
+                .iter()
+                .filter_map(|(bone, weight)| {
+                    Some((*pose.get(bone as usize)? * bind[bone as usize]) * weight)
+                })
+                .fold(Mat4::ZERO, |sum, matrix| sum + matrix)
+        };
+
+        let (vertices, normals) = self
+            .vertices
+            .iter()
+            .zip(&self.normals)
+            .zip(&self.weights)
+            .map(|((vertex, normal), weights)| {
+                let matrix = skinning(weights);
+                (
+                    matrix.transform_point3(Vec3::from(*vertex)).to_array(),
+                    matrix
+                        .transform_vector3(Vec3::from(*normal))
+                        .normalize_or_zero()
+                        .to_array(),
+                )
+            })
+            .unzip();
+        ModelSkinnedMesh { vertices, normals }
+    }
 }
 
-struct ModelStringTable {
+fn pose_bone(
+    index: usize,
+    locals: &[Mat4],
+    parents: &[Option<usize>],
+    result: &mut [Option<Mat4>],
+) -> Mat4 {
+    if let Some(matrix) = result[index] {
+        return matrix;
+    }
+    // mark as visited so a malformed hierarchy can't recurse forever
+    result[index] = Some(Mat4::IDENTITY);
+    let local = locals.get(index).copied().unwrap_or(Mat4::IDENTITY);
+    let matrix = match parents[index] {
+        Some(parent) => pose_bone(parent, locals, parents, result) * local,
+        None => local,
+    };
+    result[index] = Some(matrix);
+    matrix
+}
+
+pub(crate) struct ModelStringTable {
     arena: Bytes,
     size: usize,
 }
 
 impl ModelStringTable {
-    fn parse(bytes: &mut Bytes, size: usize) -> Result<Self, EQFilesError> {
+    pub(crate) fn parse(bytes: &mut Bytes, size: usize) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < size {
+            return Err(EQFilesError::Truncated("string table"));
+        }
         Ok(Self {
             arena: bytes.copy_to_bytes(size),
             size,
@@ -83,31 +328,33 @@ impl ModelStringTable {
         if offset > self.size {
             return Err(EQFilesError::UnknownError);
         }
-        let mut temp = Vec::new();
-        let mut it = self.arena.clone();
-        it.advance(offset);
-        let mut c = it.get_u8();
-        while c != 0 {
-            temp.push(c);
-            c = it.get_u8();
-        }
-        Ok(String::from_utf8(temp)?)
+        // the last string may run to the end of the table without a terminator
+        let string = &self.arena[offset..];
+        let length = string.iter().position(|c| *c == 0).unwrap_or(string.len());
+        Ok(String::from_utf8(string[..length].to_vec())?)
     }
 }
 
-struct ModelMaterial {
-    index: u32,
-    material_name: String,
-    shader_name: String,
+#[derive(Clone, Debug)]
+pub struct ModelMaterial {
+    pub index: u32,
+    pub material_name: String,
+    pub shader_name: String,
     properties: ModelMaterialProperties,
 }
 
 impl ModelMaterial {
-    fn parse(bytes: &mut Bytes, strings: &ModelStringTable) -> Result<Self, EQFilesError> {
+    pub(crate) fn parse(
+        bytes: &mut Bytes,
+        strings: &ModelStringTable,
+    ) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < 12 {
+            return Err(EQFilesError::Truncated("material"));
+        }
         let index = bytes.get_u32_le();
         let material_name = strings.get_string(bytes.get_u32_le() as usize)?;
         let shader_name = strings.get_string(bytes.get_u32_le() as usize)?;
-        let properties = ModelMaterialProperties::parse(bytes, &strings)?;
+        let properties = ModelMaterialProperties::parse(bytes, strings)?;
 
         Ok(Self {
             index,
@@ -116,39 +363,101 @@ impl ModelMaterial {
             properties,
         })
     }
+
+    /// Every property, e.g. `e_TextureDiffuse0` or `e_fShininess0`.
+    pub fn properties(&self) -> impl Iterator<Item = (&str, &ModelMaterialPropertyValue)> {
+        self.properties
+            .0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn property(&self, name: &str) -> Option<&ModelMaterialPropertyValue> {
+        self.properties.0.get(name)
+    }
+
+    pub fn diffuse_texture(&self) -> Option<&str> {
+        self.property("e_TextureDiffuse0")?.as_str()
+    }
+
+    pub fn normal_map(&self) -> Option<&str> {
+        self.property("e_TextureNormal0")?.as_str()
+    }
+
+    /// `e_fOpacity`, or fully opaque when the material doesn't set it.
+    pub fn opacity(&self) -> f32 {
+        self.property("e_fOpacity")
+            .and_then(ModelMaterialPropertyValue::as_f32)
+            .unwrap_or(1.0)
+    }
+
+    /// Fully transparent, so faces using it are never seen.
+    pub fn is_invisible(&self) -> bool {
+        self.opacity() <= 0.0
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct ModelMaterialProperties(BTreeMap<String, ModelMaterialPropertyValue>);
 
 impl ModelMaterialProperties {
     fn parse(bytes: &mut Bytes, strings: &ModelStringTable) -> Result<Self, EQFilesError> {
         let mut result = BTreeMap::new();
+        if bytes.remaining() < 4 {
+            return Err(EQFilesError::Truncated("material properties"));
+        }
         let count = bytes.get_u32_le();
         for _ in 0..count {
+            // name, type and value
+            if bytes.remaining() < 12 {
+                return Err(EQFilesError::Truncated("material properties"));
+            }
             let name = strings.get_string(bytes.get_u32_le() as usize)?;
-            match bytes.get_u32_le() {
-                0 => {
-                    result.insert(name, ModelMaterialPropertyValue::Float(bytes.get_f32_le()));
+            let value = match bytes.get_u32_le() {
+                0 => ModelMaterialPropertyValue::Float(bytes.get_f32_le()),
+                1 => ModelMaterialPropertyValue::Int(bytes.get_u32_le()),
+                2 => ModelMaterialPropertyValue::String(
+                    strings.get_string(bytes.get_u32_le() as usize)?,
+                ),
+                3 => {
+                    let [b, g, r, a] = bytes.get_u32_le().to_le_bytes();
+                    ModelMaterialPropertyValue::Color([r, g, b, a])
                 }
-                2 => {
-                    result.insert(
-                        name,
-                        ModelMaterialPropertyValue::String(
-                            strings.get_string(bytes.get_u32_le() as usize)?,
-                        ),
-                    );
+                property_type => {
+                    return Err(EQFilesError::UnknownMaterialPropertyType(property_type))
                 }
-                _ => todo!(),
-            }
+            };
+            result.insert(name, value);
         }
 
         Ok(ModelMaterialProperties(result))
     }
 }
 
-#[derive(Debug)]
-enum ModelMaterialPropertyValue {
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModelMaterialPropertyValue {
     Float(f32),
+    Int(u32),
+    /// Usually a texture filename.
     String(String),
+    /// RGBA, stored as ARGB.
+    Color([u8; 4]),
+}
+
+impl ModelMaterialPropertyValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Ints are converted too.
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Self::Float(value) => Some(*value),
+            Self::Int(value) => Some(*value as f32),
+            _ => None,
+        }
+    }
 }
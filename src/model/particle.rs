@@ -0,0 +1,175 @@
+use bytes::Buf;
+use bytes::Bytes;
+use glam::EulerRot;
+use glam::Mat4;
+use glam::Quat;
+use glam::Vec3;
+
+use super::Model;
+use crate::utils::read_vec3;
+use crate::EQFilesError;
+
+const NAME_LENGTH: usize = 64;
+const HEADER_SIZE: usize = 12;
+/// Two names, then translation, rotation and scale.
+const POINT_SIZE: usize = NAME_LENGTH * 2 + 36;
+
+/// Points on a model's bones where particle emitters are attached (`.pts`).
+#[derive(Clone, Debug)]
+pub struct ParticlePoints {
+    pub version: u32,
+    pub points: Vec<ParticlePoint>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParticlePoint {
+    pub name: String,
+    pub bone: String,
+    pub translation: Vec3,
+    /// Euler angles in radians, applied Z, then Y, then X.
+    pub rotation: Vec3,
+    pub scale: Vec3,
+}
+
+impl ParticlePoints {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < HEADER_SIZE {
+            return Err(EQFilesError::Truncated("particle point header"));
+        }
+        let magic = bytes.get_u32_le();
+        if magic != 0x54505145 {
+            return Err(EQFilesError::InvalidMagicNumber(magic));
+        }
+        let count = bytes.get_u32_le() as usize;
+        let version = bytes.get_u32_le();
+        if bytes.remaining() < count.saturating_mul(POINT_SIZE) {
+            return Err(EQFilesError::Truncated("particle points"));
+        }
+
+        let mut points = Vec::with_capacity(count);
+        for _ in 0..count {
+            points.push(ParticlePoint {
+                name: name(bytes)?,
+                bone: name(bytes)?,
+                translation: read_vec3(bytes),
+                rotation: read_vec3(bytes),
+                scale: read_vec3(bytes),
+            });
+        }
+
+        Ok(Self { version, points })
+    }
+
+    pub fn point(&self, name: &str) -> Option<&ParticlePoint> {
+        self.points
+            .iter()
+            .find(|point| point.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl ParticlePoint {
+    /// Index of the bone of `model` the point is attached to.
+    pub fn bone_index(&self, model: &Model) -> Option<usize> {
+        model
+            .bones
+            .iter()
+            .position(|bone| bone.name.eq_ignore_ascii_case(&self.bone))
+    }
+
+    /// Transform relative to the bone.
+    pub fn local_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            self.scale,
+            Quat::from_euler(
+                EulerRot::ZYX,
+                self.rotation.z,
+                self.rotation.y,
+                self.rotation.x,
+            ),
+            self.translation,
+        )
+    }
+
+    /// Model space transform given a pose of `model`, e.g. from [Model::bind_pose] or
+    /// [crate::Animation::sample].
+    pub fn model_matrix(&self, model: &Model, pose: &[Mat4]) -> Mat4 {
+        let bone = self
+            .bone_index(model)
+            .and_then(|index| pose.get(index))
+            .copied()
+            .unwrap_or(Mat4::IDENTITY);
+        bone * self.local_matrix()
+    }
+}
+
+/// Particle emitter settings (`.prt`), each played at one of the [ParticlePoints].
+#[derive(Clone, Debug)]
+pub struct ParticleDefinitions {
+    pub version: u32,
+    pub definitions: Vec<ParticleDefinition>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParticleDefinition {
+    /// Particle effect id, as used by spell effects.
+    pub id: u32,
+    /// Name of the [ParticlePoint] the emitter is attached to.
+    pub point: String,
+    pub unknown1: [u32; 5],
+    /// How long the emitter plays, `None` when it plays for as long as the model is shown.
+    pub duration_ms: Option<u32>,
+    pub unknown2: [u32; 3],
+    /// Only present from version 5.
+    pub unknown3: Option<u32>,
+}
+
+impl ParticleDefinitions {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < HEADER_SIZE {
+            return Err(EQFilesError::Truncated("particle definition header"));
+        }
+        let magic = bytes.get_u32_le();
+        if magic != 0x4c435450 {
+            return Err(EQFilesError::InvalidMagicNumber(magic));
+        }
+        let count = bytes.get_u32_le() as usize;
+        let version = bytes.get_u32_le();
+        let entry_size = match version {
+            4 => 4 + NAME_LENGTH + 36,
+            5 => 4 + NAME_LENGTH + 40,
+            _ => {
+                return Err(EQFilesError::UnsupportedVersion(
+                    "particle definition",
+                    version,
+                ))
+            }
+        };
+        if bytes.remaining() < count.saturating_mul(entry_size) {
+            return Err(EQFilesError::Truncated("particle definitions"));
+        }
+
+        let mut definitions = Vec::with_capacity(count);
+        for _ in 0..count {
+            definitions.push(ParticleDefinition {
+                id: bytes.get_u32_le(),
+                point: name(bytes)?,
+                unknown1: std::array::from_fn(|_| bytes.get_u32_le()),
+                duration_ms: Some(bytes.get_u32_le()).filter(|duration| *duration != u32::MAX),
+                unknown2: std::array::from_fn(|_| bytes.get_u32_le()),
+                unknown3: (version >= 5).then(|| bytes.get_u32_le()),
+            });
+        }
+
+        Ok(Self {
+            version,
+            definitions,
+        })
+    }
+}
+
+/// A fixed size, null terminated name. What follows the terminator isn't always zeroed.
+fn name(bytes: &mut Bytes) -> Result<String, EQFilesError> {
+    let name = bytes.copy_to_bytes(NAME_LENGTH);
+    let length = name.iter().position(|c| *c == 0).unwrap_or(NAME_LENGTH);
+    Ok(String::from_utf8(name[..length].to_vec())?)
+}
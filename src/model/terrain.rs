@@ -0,0 +1,68 @@
+use bytes::Buf;
+use bytes::Bytes;
+
+use super::parse_triangles;
+use super::triangle_material;
+use super::ModelMaterial;
+use super::ModelStringTable;
+use super::ModelTriangle;
+use super::ModelVertices;
+use crate::EQFilesError;
+
+const HEADER_SIZE: usize = 24;
+
+/// EQG zone terrain (`.ter`), laid out like a [crate::Model] without bones.
+#[derive(Clone, Debug)]
+pub struct Terrain {
+    pub version: u32,
+    pub materials: Vec<ModelMaterial>,
+    pub vertices: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    /// Version 3 and later only, RGBA.
+    pub colors: Vec<[u8; 4]>,
+    /// Version 3 and later only.
+    pub secondary_uvs: Vec<[f32; 2]>,
+    pub triangles: Vec<ModelTriangle>,
+}
+
+impl Terrain {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < HEADER_SIZE {
+            return Err(EQFilesError::Truncated("terrain header"));
+        }
+        let magic = bytes.get_u32_le();
+        if magic != 0x54475145 {
+            return Err(EQFilesError::InvalidMagicNumber(magic));
+        }
+        let version = bytes.get_u32_le();
+        let strings_length = bytes.get_u32_le();
+        let material_count = bytes.get_u32_le();
+        let vertex_count = bytes.get_u32_le();
+        let triangle_count = bytes.get_u32_le();
+
+        let strings = ModelStringTable::parse(bytes, strings_length as usize)?;
+        let mut materials = Vec::new();
+        for _ in 0..material_count {
+            materials.push(ModelMaterial::parse(bytes, &strings)?);
+        }
+        let vertices = ModelVertices::parse(bytes, version, vertex_count)?;
+        let triangles = parse_triangles(bytes, triangle_count)?;
+
+        Ok(Self {
+            version,
+            materials,
+            vertices: vertices.positions,
+            normals: vertices.normals,
+            uvs: vertices.uvs,
+            colors: vertices.colors,
+            secondary_uvs: vertices.secondary_uvs,
+            triangles,
+        })
+    }
+
+    /// The material a triangle uses.
+    pub fn material(&self, triangle: &ModelTriangle) -> Option<&ModelMaterial> {
+        triangle_material(&self.materials, triangle)
+    }
+}
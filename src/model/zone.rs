@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use bytes::Buf;
+use bytes::Bytes;
+use glam::EulerRot;
+use glam::Mat4;
+use glam::Quat;
+use glam::Vec3;
+
+use super::ModelStringTable;
+use crate::utils::check_remaining;
+use crate::utils::read_vec3;
+use crate::EQFilesError;
+
+const HEADER_SIZE: usize = 28;
+
+/// An EQG zone definition (`.zon`): the model files the zone is built from and where their
+/// instances are placed, plus its regions and lights.
+///
+/// Heightmap zones use a text `.zon` instead (starting with `EQTZP`), which only sets the
+/// parameters in [ZoneDefinition::properties].
+#[derive(Clone, Debug, Default)]
+pub struct ZoneDefinition {
+    pub version: u32,
+    /// Filenames of the `.mod` and `.ter` files used, e.g. `zone.ter`.
+    pub models: Vec<String>,
+    pub objects: Vec<ZoneObject>,
+    pub regions: Vec<ZoneRegion>,
+    pub lights: Vec<ZoneLight>,
+    /// `*NAME` style settings of a text definition, keyed without the `*`.
+    pub properties: BTreeMap<String, String>,
+}
+
+/// An instance of one of [ZoneDefinition::models].
+#[derive(Clone, Debug)]
+pub struct ZoneObject {
+    /// Filename of the model placed, if the index was valid.
+    pub model: Option<String>,
+    pub name: String,
+    pub position: Vec3,
+    /// Euler angles in radians, applied Z, then Y, then X.
+    pub rotation: Vec3,
+    pub scale: f32,
+}
+
+impl ZoneObject {
+    /// Model to zone space.
+    pub fn transform(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            Vec3::splat(self.scale),
+            Quat::from_euler(
+                EulerRot::ZYX,
+                self.rotation.z,
+                self.rotation.y,
+                self.rotation.x,
+            ),
+            self.position,
+        )
+    }
+}
+
+/// A box shaped area, e.g. water or a zone line. What it does is encoded in its name.
+#[derive(Clone, Debug)]
+pub struct ZoneRegion {
+    pub name: String,
+    pub centre: Vec3,
+    pub unknown: f32,
+    pub flags: [u32; 2],
+    /// Half size on each axis.
+    pub extent: Vec3,
+}
+
+#[derive(Clone, Debug)]
+pub struct ZoneLight {
+    pub name: String,
+    pub position: Vec3,
+    pub color: Vec3,
+    pub radius: f32,
+}
+
+impl ZoneDefinition {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if bytes.starts_with(b"EQTZP") {
+            return Self::parse_text(bytes);
+        }
+        if bytes.remaining() < HEADER_SIZE {
+            return Err(EQFilesError::Truncated("zone header"));
+        }
+
+        let magic = bytes.get_u32_le();
+        if magic != 0x5a475145 {
+            return Err(EQFilesError::InvalidMagicNumber(magic));
+        }
+        let version = bytes.get_u32_le();
+        let strings_length = bytes.get_u32_le();
+        let model_count = bytes.get_u32_le();
+        let object_count = bytes.get_u32_le();
+        let region_count = bytes.get_u32_le();
+        let light_count = bytes.get_u32_le();
+
+        let strings = ModelStringTable::parse(bytes, strings_length as usize)?;
+        check_remaining(bytes, model_count as usize, 4, "zone models")?;
+        let mut models = Vec::new();
+        for _ in 0..model_count {
+            models.push(strings.get_string(bytes.get_u32_le() as usize)?);
+        }
+
+        check_remaining(bytes, object_count as usize, 36, "zone objects")?;
+        let mut objects = Vec::new();
+        for _ in 0..object_count {
+            let model_index = bytes.get_i32_le();
+            let name = strings.get_string(bytes.get_u32_le() as usize)?;
+            objects.push(ZoneObject {
+                model: usize::try_from(model_index)
+                    .ok()
+                    .and_then(|index| models.get(index).cloned()),
+                name,
+                position: read_vec3(bytes),
+                rotation: read_vec3(bytes),
+                scale: bytes.get_f32_le(),
+            });
+        }
+
+        check_remaining(bytes, region_count as usize, 40, "zone regions")?;
+        let mut regions = Vec::new();
+        for _ in 0..region_count {
+            regions.push(ZoneRegion {
+                name: strings.get_string(bytes.get_u32_le() as usize)?,
+                centre: read_vec3(bytes),
+                unknown: bytes.get_f32_le(),
+                flags: [bytes.get_u32_le(), bytes.get_u32_le()],
+                extent: read_vec3(bytes),
+            });
+        }
+
+        check_remaining(bytes, light_count as usize, 32, "zone lights")?;
+        let mut lights = Vec::new();
+        for _ in 0..light_count {
+            lights.push(ZoneLight {
+                name: strings.get_string(bytes.get_u32_le() as usize)?,
+                position: read_vec3(bytes),
+                color: read_vec3(bytes),
+                radius: bytes.get_f32_le(),
+            });
+        }
+
+        Ok(Self {
+            version,
+            models,
+            objects,
+            regions,
+            lights,
+            properties: BTreeMap::new(),
+        })
+    }
+
+    fn parse_text(bytes: &Bytes) -> Result<Self, EQFilesError> {
+        let text = String::from_utf8(bytes.to_vec())?;
+        let properties = text
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix('*'))
+            .map(|line| match line.split_once(char::is_whitespace) {
+                Some((key, value)) => (key.to_string(), value.trim().to_string()),
+                None => (line.to_string(), String::new()),
+            })
+            .collect();
+
+        Ok(Self {
+            version: 4,
+            properties,
+            ..Default::default()
+        })
+    }
+
+    /// Instances of the model named `filename`, ignoring case.
+    pub fn objects_of<'a>(&'a self, filename: &'a str) -> impl Iterator<Item = &'a ZoneObject> {
+        self.objects.iter().filter(move |object| {
+            object
+                .model
+                .as_deref()
+                .is_some_and(|model| model.eq_ignore_ascii_case(filename))
+        })
+    }
+}
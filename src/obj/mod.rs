@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use glam::Mat4;
+use glam::Vec3;
+
+use crate::utils::to_y_up;
+use crate::Model;
+use crate::WldFile;
+use crate::WldMesh;
+use crate::WldModel;
+
+/// Accumulates meshes into a single Wavefront OBJ with a companion MTL. Faces are grouped
+/// per material across all the meshes added, and everything is written Y up.
+#[derive(Debug, Default)]
+pub struct ObjWriter {
+    vertices: String,
+    groups: BTreeMap<String, String>,
+    materials: BTreeMap<String, Option<String>>,
+    vertex_count: usize,
+    uv_count: usize,
+    normal_count: usize,
+}
+
+impl ObjWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every mesh of `wld`, see [WldFile::meshes].
+    pub fn add_wld(&mut self, wld: &WldFile) {
+        for mesh in wld.meshes() {
+            self.add_wld_mesh(wld, &mesh, None);
+        }
+    }
+
+    /// Adds `mesh`, optionally moved by `transform` (in EQ space) to place an object in the
+    /// world. Materials are looked up in `wld`.
+    pub fn add_wld_mesh(&mut self, wld: &WldFile, mesh: &WldMesh, transform: Option<Mat4>) {
+        let centre = Vec3::new(mesh.centre.0, mesh.centre.1, mesh.centre.2);
+        let positions = mesh
+            .position
+            .iter()
+            .map(|p| (Vec3::from(*p) + centre).to_array())
+            .collect::<Vec<_>>();
+
+        let materials = wld
+            .resolve_material_list(mesh.material_list_ref)
+            .into_iter()
+            .enumerate()
+            .map(|(i, material)| {
+                let name = material.name.unwrap_or_else(|| format!("material_{}", i));
+                (name, material.textures.into_iter().next())
+            })
+            .collect::<Vec<_>>();
+
+        let mut faces = Vec::new();
+        for (material, triangles) in mesh.material_triangles() {
+            let material = material.and_then(|material| materials.get(material));
+            for [_flags, a, b, c] in triangles {
+                faces.push(([*a as u32, *b as u32, *c as u32], material.cloned()));
+            }
+        }
+
+        self.add(
+            &positions,
+            &mesh.uv,
+            &mesh.normal,
+            faces.into_iter(),
+            transform,
+        );
+    }
+
+    /// Adds the objects placed in `objects`, a zone's `objects.wld`, in world space using the
+    /// models defined in `models`, usually its `<zone>_obj.wld`.
+    pub fn add_wld_objects(&mut self, objects: &WldFile, models: &WldFile) {
+        for location in objects.object_locations() {
+            let Some(model) = location
+                .model_name
+                .clone()
+                .and_then(|name| models.fragment_by_name::<WldModel>(name))
+            else {
+                continue;
+            };
+            for mesh in models.model_meshes(&model) {
+                self.add_wld_mesh(models, &mesh, Some(location.transform()));
+            }
+        }
+    }
+
+    /// Adds an EQG model, optionally moved by `transform` (in EQ space).
+    pub fn add_model(&mut self, model: &Model, transform: Option<Mat4>) {
+        let faces = model
+            .triangles
+            .iter()
+            .map(|triangle| {
+                let material = model.material(triangle).map(|material| {
+                    (
+                        material.material_name.clone(),
+                        material.diffuse_texture().map(String::from),
+                    )
+                });
+                (triangle.vertices, material)
+            })
+            .collect::<Vec<_>>();
+
+        self.add(
+            &model.vertices,
+            &model.uvs,
+            &model.normals,
+            faces.into_iter(),
+            transform,
+        );
+    }
+
+    fn add(
+        &mut self,
+        positions: &[[f32; 3]],
+        uvs: &[[f32; 2]],
+        normals: &[[f32; 3]],
+        faces: impl Iterator<Item = ([u32; 3], Option<(String, Option<String>)>)>,
+        transform: Option<Mat4>,
+    ) {
+        let transform = transform.unwrap_or(Mat4::IDENTITY);
+        let has_uvs = uvs.len() == positions.len();
+        let has_normals = normals.len() == positions.len();
+
+        for position in positions {
+            let [x, y, z] = to_y_up(transform.transform_point3(Vec3::from(*position)).to_array());
+            let _ = writeln!(self.vertices, "v {} {} {}", x, y, z);
+        }
+        if has_uvs {
+            // OBJ puts the texture origin at the bottom left, EQ at the top left
+            for [u, v] in uvs {
+                let _ = writeln!(self.vertices, "vt {} {}", u, 1.0 - v);
+            }
+        }
+        if has_normals {
+            for normal in normals {
+                let normal = transform
+                    .transform_vector3(Vec3::from(*normal))
+                    .normalize_or_zero()
+                    .to_array();
+                let [x, y, z] = to_y_up(normal);
+                let _ = writeln!(self.vertices, "vn {} {} {}", x, y, z);
+            }
+        }
+
+        for (indices, material) in faces {
+            let (name, texture) = match material {
+                Some((name, texture)) => (sanitize(&name), texture),
+                None => (String::from("default"), None),
+            };
+            self.materials.entry(name.clone()).or_insert(texture);
+            let group = self.groups.entry(name).or_default();
+            group.push('f');
+            for index in indices {
+                let vertex = self.vertex_count + index as usize + 1;
+                let uv = self.uv_count + index as usize + 1;
+                let normal = self.normal_count + index as usize + 1;
+                let _ = match (has_uvs, has_normals) {
+                    (true, true) => write!(group, " {}/{}/{}", vertex, uv, normal),
+                    (true, false) => write!(group, " {}/{}", vertex, uv),
+                    (false, true) => write!(group, " {}//{}", vertex, normal),
+                    (false, false) => write!(group, " {}", vertex),
+                };
+            }
+            group.push('\n');
+        }
+
+        self.vertex_count += positions.len();
+        if has_uvs {
+            self.uv_count += uvs.len();
+        }
+        if has_normals {
+            self.normal_count += normals.len();
+        }
+    }
+
+    /// The OBJ contents, referencing the MTL as `mtl_filename`.
+    pub fn obj(&self, mtl_filename: &str) -> String {
+        let mut result = format!("mtllib {}\n", mtl_filename);
+        result.push_str(&self.vertices);
+        for (name, faces) in &self.groups {
+            let _ = write!(result, "g {}\nusemtl {}\n{}", name, name, faces);
+        }
+        result
+    }
+
+    pub fn mtl(&self) -> String {
+        let mut result = String::new();
+        for (name, texture) in &self.materials {
+            let _ = writeln!(result, "newmtl {}\nKd 1 1 1", name);
+            if let Some(texture) = texture {
+                let _ = writeln!(result, "map_Kd {}", texture);
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect()
+}
@@ -1,6 +1,7 @@
 mod block;
 mod entry;
 mod header;
+mod writer;
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -8,6 +9,7 @@ use std::sync::Arc;
 use bytes::Buf;
 use bytes::Bytes;
 
+pub use self::writer::PackFileWriter;
 use crate::utils::*;
 use crate::Decoder;
 use crate::EQFilesError;
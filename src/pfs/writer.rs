@@ -0,0 +1,131 @@
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::PackFile;
+
+const MAGIC_NUMBER: u32 = 0x20534650; // "PFS "
+const VERSION: u32 = 0x20000;
+const BLOCK_SIZE: usize = 8192;
+/// The directory entry doesn't hash a filename, it always uses this.
+const DIRECTORY_CRC: u32 = 0x61580AC9;
+
+/// Builds a pack file (`.s3d`, `.eqg`, `.pfs`) from scratch, or from an existing one to
+/// replace some of its files.
+#[derive(Clone, Debug, Default)]
+pub struct PackFileWriter {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl PackFileWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file, replacing any with the same name ignoring case.
+    pub fn insert(&mut self, filename: &str, contents: impl Into<Vec<u8>>) {
+        let contents = contents.into();
+        match self
+            .files
+            .iter_mut()
+            .find(|(name, _)| name.eq_ignore_ascii_case(filename))
+        {
+            Some((_, existing)) => *existing = contents,
+            None => self.files.push((filename.to_lowercase(), contents)),
+        }
+    }
+
+    /// Returns whether a file with that name was present.
+    pub fn remove(&mut self, filename: &str) -> bool {
+        let count = self.files.len();
+        self.files
+            .retain(|(name, _)| !name.eq_ignore_ascii_case(filename));
+        self.files.len() != count
+    }
+
+    pub fn filenames(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut directory = Vec::new();
+        directory.extend_from_slice(&(self.files.len() as u32).to_le_bytes());
+        for (name, _) in &self.files {
+            directory.extend_from_slice(&(name.len() as u32 + 1).to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+            directory.push(0);
+        }
+
+        let mut result = vec![0; 12];
+        // the directory has to come last, as readers match names to files by offset
+        let mut entries = Vec::with_capacity(self.files.len() + 1);
+        for (crc, contents) in self
+            .files
+            .iter()
+            .map(|(name, contents)| (filename_crc(name), contents))
+            .chain(std::iter::once((DIRECTORY_CRC, &directory)))
+        {
+            entries.push((crc, result.len() as u32, contents.len() as u32));
+            // an empty block keeps empty files from sharing an offset with the next one
+            let empty = contents.is_empty().then_some(&[][..]);
+            for chunk in contents.chunks(BLOCK_SIZE).chain(empty) {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+                encoder
+                    .write_all(chunk)
+                    .expect("compressing into memory cannot fail");
+                let compressed = encoder
+                    .finish()
+                    .expect("compressing into memory cannot fail");
+                result.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+                result.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+                result.extend_from_slice(&compressed);
+            }
+        }
+
+        let directory_offset = result.len() as u32;
+        result[0..4].copy_from_slice(&directory_offset.to_le_bytes());
+        result[4..8].copy_from_slice(&MAGIC_NUMBER.to_le_bytes());
+        result[8..12].copy_from_slice(&VERSION.to_le_bytes());
+
+        entries.sort_by_key(|(crc, _, _)| *crc);
+        result.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (crc, pointer, size) in entries {
+            result.extend_from_slice(&crc.to_le_bytes());
+            result.extend_from_slice(&pointer.to_le_bytes());
+            result.extend_from_slice(&size.to_le_bytes());
+        }
+        result.extend_from_slice(b"STEVE");
+        result.extend_from_slice(&0u32.to_le_bytes());
+        result
+    }
+}
+
+impl From<PackFile> for PackFileWriter {
+    fn from(pack: PackFile) -> Self {
+        Self {
+            files: pack
+                .files()
+                .map(|(name, contents)| (name, contents.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+/// CRC-32 (polynomial 0x04C11DB7, not reflected, no initial or final xor) of the lowercase
+/// filename including its null terminator.
+pub(crate) fn filename_crc(filename: &str) -> u32 {
+    filename
+        .to_lowercase()
+        .bytes()
+        .chain(std::iter::once(0))
+        .fold(0u32, |crc, byte| {
+            (0..8).fold(crc ^ ((byte as u32) << 24), |crc, _| {
+                if crc & 0x80000000 != 0 {
+                    (crc << 1) ^ 0x04C11DB7
+                } else {
+                    crc << 1
+                }
+            })
+        })
+}
@@ -0,0 +1,154 @@
+use bytes::Buf;
+use bytes::Bytes;
+use glam::Vec3;
+
+use super::archive_sounds;
+use super::SoundEmitter;
+use super::SoundEmitterKind;
+use crate::text::decode;
+use crate::EQFilesError;
+use crate::PackFile;
+
+const RECORD_SIZE: usize = 84;
+/// Ids up to this one index the `EMIT` section of the sound bank.
+const LAST_EMIT_ID: i32 = 31;
+/// Ids from this one index the `LOOP` section, those in between are built into the client.
+const FIRST_LOOP_ID: i32 = 162;
+
+/// The emitters of a `<zone>_sounds.eff` file. Their sounds are ids resolved through the
+/// zone's [SoundBank].
+#[derive(Clone, Debug, Default)]
+pub struct SoundEffects {
+    pub records: Vec<SoundEffectRecord>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SoundEffectRecord {
+    pub sequence: u32,
+    pub position: Vec3,
+    pub radius: f32,
+    pub cooldown_ms: [u32; 2],
+    pub random_delay_ms: u32,
+    pub sound_ids: [i32; 2],
+    pub kind: SoundEmitterKind,
+    /// Distance from which the sound is at full volume, for [SoundEmitterKind::DayNight]
+    /// and [SoundEmitterKind::Static].
+    pub as_distance: i32,
+    pub fade_out_ms: i32,
+    pub full_volume_range: i32,
+}
+
+impl SoundEffects {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if !bytes.len().is_multiple_of(RECORD_SIZE) {
+            return Err(EQFilesError::InvalidSoundFile(
+                "size is not a multiple of 84",
+            ));
+        }
+
+        let mut records = Vec::with_capacity(bytes.len() / RECORD_SIZE);
+        while bytes.has_remaining() {
+            let _unknown = [bytes.get_u32_le(), bytes.get_u32_le(), bytes.get_u32_le()];
+            let sequence = bytes.get_u32_le();
+            let position = Vec3::new(bytes.get_f32_le(), bytes.get_f32_le(), bytes.get_f32_le());
+            let radius = bytes.get_f32_le();
+            let cooldown_ms = [bytes.get_u32_le(), bytes.get_u32_le()];
+            let random_delay_ms = bytes.get_u32_le();
+            let _unknown = bytes.get_u32_le();
+            let sound_ids = [bytes.get_i32_le(), bytes.get_i32_le()];
+            let kind = SoundEmitterKind::from(bytes.get_u8());
+            bytes.advance(3);
+            let as_distance = bytes.get_i32_le();
+            let _unknown = bytes.get_u32_le();
+            let fade_out_ms = bytes.get_i32_le();
+            let _unknown = bytes.get_u32_le();
+            let full_volume_range = bytes.get_i32_le();
+            let _unknown = bytes.get_u32_le();
+
+            records.push(SoundEffectRecord {
+                sequence,
+                position,
+                radius,
+                cooldown_ms,
+                random_delay_ms,
+                sound_ids,
+                kind,
+                as_distance,
+                fade_out_ms,
+                full_volume_range,
+            });
+        }
+        Ok(Self { records })
+    }
+
+    /// Resolves every record's sounds with `bank` to filenames in `archives`, the zone's
+    /// `snd*.pfs` files. Music and sounds built into the client have no filename.
+    pub fn emitters(&self, bank: &SoundBank, archives: &[&PackFile]) -> Vec<SoundEmitter> {
+        let sounds = archive_sounds(archives);
+        let sound = |id: i32, kind: SoundEmitterKind| {
+            if kind == SoundEmitterKind::Music {
+                return None;
+            }
+            sounds.get(&bank.name(id)?.to_lowercase()).cloned()
+        };
+
+        self.records
+            .iter()
+            .map(|record| {
+                let day_sound = sound(record.sound_ids[0], record.kind);
+                let night_sound = match record.kind {
+                    SoundEmitterKind::DayNight | SoundEmitterKind::Background => {
+                        sound(record.sound_ids[1], record.kind)
+                    }
+                    _ => None,
+                };
+                SoundEmitter {
+                    position: record.position,
+                    radius: record.radius,
+                    kind: record.kind,
+                    day_sound,
+                    night_sound,
+                    volume: None,
+                    cooldown_ms: record.cooldown_ms[0],
+                    random_delay_ms: record.random_delay_ms,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Sound names of a `<zone>_sndbnk.eff` file, split in an `EMIT` and a `LOOP` section.
+#[derive(Clone, Debug, Default)]
+pub struct SoundBank {
+    pub emit: Vec<String>,
+    pub loops: Vec<String>,
+}
+
+impl SoundBank {
+    pub fn parse(bytes: &[u8]) -> Result<Self, EQFilesError> {
+        let text = decode(bytes);
+        let mut result = Self::default();
+        let mut section = None;
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match line {
+                "EMIT" => section = Some(&mut result.emit),
+                "LOOP" => section = Some(&mut result.loops),
+                name => section
+                    .as_mut()
+                    .ok_or(EQFilesError::InvalidSoundFile("sound outside of a section"))?
+                    .push(name.to_string()),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Name of the sound a [SoundEffectRecord] refers to by id.
+    pub fn name(&self, id: i32) -> Option<&str> {
+        let name = match id {
+            1..=LAST_EMIT_ID => self.emit.get(id as usize - 1),
+            FIRST_LOOP_ID.. => self.loops.get((id - FIRST_LOOP_ID) as usize),
+            _ => None,
+        };
+        name.map(String::as_str)
+    }
+}
@@ -0,0 +1,104 @@
+use glam::Vec3;
+
+use super::archive_sounds;
+use super::SoundEmitter;
+use super::SoundEmitterKind;
+use crate::text::decode;
+use crate::EQFilesError;
+use crate::PackFile;
+
+/// The text emitter list (`.emt`) used by newer zones instead of `_sounds.eff`.
+#[derive(Clone, Debug, Default)]
+pub struct EmitterFile {
+    pub records: Vec<EmitterRecord>,
+}
+
+/// One comma separated line: the kind, the sound file, when it plays (0 always, 1 by day,
+/// 2 by night), volume, fade in and out, cooldown, random delay, position and radius.
+/// Any further columns are kept in `extra`.
+#[derive(Clone, Debug)]
+pub struct EmitterRecord {
+    pub kind: SoundEmitterKind,
+    pub sound: String,
+    pub active: u32,
+    pub volume: f32,
+    pub fade_in_ms: u32,
+    pub fade_out_ms: u32,
+    pub cooldown_ms: u32,
+    pub random_delay_ms: u32,
+    pub position: Vec3,
+    pub radius: f32,
+    pub extra: Vec<String>,
+}
+
+impl EmitterFile {
+    pub fn parse(bytes: &[u8]) -> Result<Self, EQFilesError> {
+        let text = decode(bytes);
+        let mut records = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+                continue;
+            }
+            let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+            let error = |reason| EQFilesError::InvalidTextLine(number + 1, reason);
+            if fields.len() < 12 {
+                return Err(error("missing emitter columns"));
+            }
+            let float = |index: usize| {
+                fields[index]
+                    .parse::<f32>()
+                    .map_err(|_| error("invalid number"))
+            };
+            let integer = |index: usize| float(index).map(|value| value as u32);
+
+            records.push(EmitterRecord {
+                kind: SoundEmitterKind::from(integer(0)? as u8),
+                sound: fields[1].to_string(),
+                active: integer(2)?,
+                volume: float(3)?,
+                fade_in_ms: integer(4)?,
+                fade_out_ms: integer(5)?,
+                cooldown_ms: integer(6)?,
+                random_delay_ms: integer(7)?,
+                position: Vec3::new(float(8)?, float(9)?, float(10)?),
+                radius: float(11)?,
+                extra: fields[12..].iter().map(|field| field.to_string()).collect(),
+            });
+        }
+        Ok(Self { records })
+    }
+
+    /// The emitters, with sounds matched case insensitively to filenames in `archives`.
+    /// Sounds that aren't found keep the name from the file.
+    pub fn emitters(&self, archives: &[&PackFile]) -> Vec<SoundEmitter> {
+        let sounds = archive_sounds(archives);
+        self.records
+            .iter()
+            .map(|record| {
+                let stem = record
+                    .sound
+                    .rsplit_once('.')
+                    .map_or(record.sound.as_str(), |(stem, _)| stem);
+                let sound = sounds
+                    .get(&stem.to_lowercase())
+                    .cloned()
+                    .unwrap_or_else(|| record.sound.clone());
+                let (day_sound, night_sound) = match record.active {
+                    2 => (None, Some(sound)),
+                    _ => (Some(sound), None),
+                };
+                SoundEmitter {
+                    position: record.position,
+                    radius: record.radius,
+                    kind: record.kind,
+                    day_sound,
+                    night_sound,
+                    volume: Some(record.volume),
+                    cooldown_ms: record.cooldown_ms,
+                    random_delay_ms: record.random_delay_ms,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,67 @@
+mod eff;
+mod emt;
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+
+pub use self::eff::SoundBank;
+pub use self::eff::SoundEffectRecord;
+pub use self::eff::SoundEffects;
+pub use self::emt::EmitterFile;
+pub use self::emt::EmitterRecord;
+use crate::PackFile;
+
+/// An ambient sound source placed in a zone, from either sound format.
+#[derive(Clone, Debug)]
+pub struct SoundEmitter {
+    pub position: Vec3,
+    pub radius: f32,
+    pub kind: SoundEmitterKind,
+    /// Filename in the sound archives, also used at night when there's no `night_sound`.
+    pub day_sound: Option<String>,
+    pub night_sound: Option<String>,
+    /// Between 0 and 1, if the format stores one.
+    pub volume: Option<f32>,
+    pub cooldown_ms: u32,
+    /// Extra random delay added to the cooldown.
+    pub random_delay_ms: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundEmitterKind {
+    /// Plays a different sound by day and by night.
+    DayNight,
+    /// Zone music, referencing the zone's `.xmi` tracks or, when negative, its mp3s.
+    Music,
+    /// A single sound effect.
+    Static,
+    /// Day and night sounds that cover the area rather than coming from a point.
+    Background,
+    Unknown(u8),
+}
+
+impl From<u8> for SoundEmitterKind {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::DayNight,
+            1 => Self::Music,
+            2 => Self::Static,
+            3 => Self::Background,
+            value => Self::Unknown(value),
+        }
+    }
+}
+
+/// Sound filenames of `archives` by lowercase name without extension, e.g. `bigbell` for
+/// `BigBell.wav`.
+pub(crate) fn archive_sounds(archives: &[&PackFile]) -> BTreeMap<String, String> {
+    archives
+        .iter()
+        .flat_map(|archive| archive.filenames())
+        .filter_map(|filename| {
+            let (stem, _) = filename.rsplit_once('.')?;
+            Some((stem.to_lowercase(), filename))
+        })
+        .collect()
+}
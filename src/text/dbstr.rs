@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+
+use super::decode;
+use super::lines;
+use crate::EQFilesError;
+
+/// Database strings (`dbstr_us.txt`): names and descriptions of AAs, factions, item
+/// effects and more, keyed by an id within a type.
+#[derive(Clone, Debug, Default)]
+pub struct DbStringTable {
+    pub strings: BTreeMap<(u32, u32), String>,
+}
+
+impl DbStringTable {
+    /// Each line is `id^type^text^0`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, EQFilesError> {
+        let text = decode(bytes);
+        let mut strings = BTreeMap::new();
+        for (number, line) in lines(&text) {
+            let mut fields = line.splitn(3, '^');
+            let mut number_field = |reason| {
+                fields
+                    .next()
+                    .and_then(|field| field.trim().parse().ok())
+                    .ok_or(EQFilesError::InvalidTextLine(number, reason))
+            };
+            let id = number_field("invalid string id")?;
+            let string_type = number_field("invalid string type")?;
+            let text = fields.next().unwrap_or_default();
+            let text = text.rsplit_once('^').map_or(text, |(text, _)| text);
+            strings.insert((id, string_type), text.to_string());
+        }
+        Ok(Self { strings })
+    }
+
+    pub fn get(&self, id: u32, string_type: u32) -> Option<&str> {
+        self.strings.get(&(id, string_type)).map(String::as_str)
+    }
+
+    /// Every string with `id`, by type.
+    pub fn types(&self, id: u32) -> impl Iterator<Item = (u32, &str)> {
+        self.strings
+            .range((id, 0)..=(id, u32::MAX))
+            .map(|((_, string_type), text)| (*string_type, text.as_str()))
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+
+use super::decode;
+use super::lines;
+use crate::EQFilesError;
+
+/// Client messages by id (`eqstr_us.txt`), which the server refers to instead of sending
+/// text. Messages use `%1`, `%2`... for their arguments.
+#[derive(Clone, Debug, Default)]
+pub struct StringTable {
+    pub strings: BTreeMap<u32, String>,
+}
+
+impl StringTable {
+    /// Each line is an id, a space and the message, after a two line header: `EQST` with the
+    /// version, then a line with the count.
+    pub fn parse(bytes: &[u8]) -> Result<Self, EQFilesError> {
+        let text = decode(bytes);
+        let mut lines = lines(&text).peekable();
+        if lines
+            .peek()
+            .is_some_and(|(_, line)| line.starts_with("EQST"))
+        {
+            lines.next();
+            lines.next();
+        }
+
+        let mut strings = BTreeMap::new();
+        for (number, line) in lines {
+            let (id, text) = line.split_once(' ').unwrap_or((line, ""));
+            let id = id
+                .parse()
+                .map_err(|_| EQFilesError::InvalidTextLine(number, "invalid string id"))?;
+            strings.insert(id, text.to_string());
+        }
+        Ok(Self { strings })
+    }
+
+    pub fn get(&self, id: u32) -> Option<&str> {
+        self.strings.get(&id).map(String::as_str)
+    }
+
+    /// The message with its `%1`... placeholders replaced by `arguments`.
+    pub fn format(&self, id: u32, arguments: &[&str]) -> Option<String> {
+        let mut result = self.get(id)?.to_string();
+        // backwards, so %1 doesn't match the start of %10
+        for (index, argument) in arguments.iter().enumerate().rev() {
+            result = result.replace(&format!("%{}", index + 1), argument);
+        }
+        Some(result)
+    }
+}
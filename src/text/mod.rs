@@ -0,0 +1,26 @@
+mod dbstr;
+mod eqstr;
+mod spells;
+
+use std::borrow::Cow;
+
+pub use self::dbstr::DbStringTable;
+pub use self::eqstr::StringTable;
+pub use self::spells::Spell;
+pub use self::spells::SpellDatabase;
+
+/// The client's text files are mostly ASCII, but older ones are Latin-1 rather than UTF-8.
+pub(crate) fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Cow::Borrowed(text),
+        Err(_) => Cow::Owned(bytes.iter().map(|c| *c as char).collect()),
+    }
+}
+
+/// Lines that aren't blank, numbered from 1 for errors.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
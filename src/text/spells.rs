@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::decode;
+use super::lines;
+use crate::EQFilesError;
+
+/// The client spell list (`spells_us.txt`).
+#[derive(Clone, Debug, Default)]
+pub struct SpellDatabase {
+    pub spells: BTreeMap<u32, Spell>,
+}
+
+/// One line of `spells_us.txt`. The leading columns every client version shares are
+/// decoded, missing or invalid numbers read as 0; read the rest with [Spell::field].
+#[derive(Clone, Debug)]
+pub struct Spell {
+    pub id: u32,
+    pub name: String,
+    /// Shown to the target when the spell lands on them.
+    pub cast_on_you: Option<String>,
+    /// Shown to others when the spell lands, following the target's name.
+    pub cast_on_other: Option<String>,
+    /// Shown to the target when the spell wears off.
+    pub spell_fades: Option<String>,
+    pub range: f32,
+    pub aoe_range: f32,
+    pub pushback: f32,
+    pub pushup: f32,
+    pub cast_time_ms: u32,
+    pub recovery_time_ms: u32,
+    pub recast_time_ms: u32,
+    /// How the duration scales with the caster's level.
+    pub buff_duration_formula: u32,
+    /// Duration in ticks of six seconds, capped by the formula.
+    pub buff_duration: u32,
+    pub mana: u32,
+    pub fields: Vec<String>,
+}
+
+impl SpellDatabase {
+    /// Each line is a spell's fields separated by `^`, starting with its id and name.
+    pub fn parse(bytes: &[u8]) -> Result<Self, EQFilesError> {
+        let text = decode(bytes);
+        let mut spells = BTreeMap::new();
+        for (number, line) in lines(&text) {
+            let fields = line.split('^').map(String::from).collect::<Vec<_>>();
+            let id = fields[0]
+                .trim()
+                .parse()
+                .map_err(|_| EQFilesError::InvalidTextLine(number, "invalid spell id"))?;
+            let name = fields
+                .get(1)
+                .cloned()
+                .ok_or(EQFilesError::InvalidTextLine(number, "missing spell name"))?;
+            spells.insert(id, Spell::new(id, name, fields));
+        }
+        Ok(Self { spells })
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Spell> {
+        self.spells.get(&id)
+    }
+
+    /// Every spell called `name`, ignoring case. Ranks of a spell share a name.
+    pub fn by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Spell> {
+        self.spells
+            .values()
+            .filter(move |spell| spell.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl Spell {
+    fn new(id: u32, name: String, fields: Vec<String>) -> Self {
+        let mut spell = Self {
+            id,
+            name,
+            cast_on_you: None,
+            cast_on_other: None,
+            spell_fades: None,
+            range: 0.0,
+            aoe_range: 0.0,
+            pushback: 0.0,
+            pushup: 0.0,
+            cast_time_ms: 0,
+            recovery_time_ms: 0,
+            recast_time_ms: 0,
+            buff_duration_formula: 0,
+            buff_duration: 0,
+            mana: 0,
+            fields,
+        };
+        spell.cast_on_you = spell.field(6);
+        spell.cast_on_other = spell.field(7);
+        spell.spell_fades = spell.field(8);
+        spell.range = spell.field(9).unwrap_or_default();
+        spell.aoe_range = spell.field(10).unwrap_or_default();
+        spell.pushback = spell.field(11).unwrap_or_default();
+        spell.pushup = spell.field(12).unwrap_or_default();
+        spell.cast_time_ms = spell.field(13).unwrap_or_default();
+        spell.recovery_time_ms = spell.field(14).unwrap_or_default();
+        spell.recast_time_ms = spell.field(15).unwrap_or_default();
+        spell.buff_duration_formula = spell.field(16).unwrap_or_default();
+        spell.buff_duration = spell.field(17).unwrap_or_default();
+        spell.mana = spell.field(19).unwrap_or_default();
+        spell
+    }
+
+    /// Field `index` of the line (the id is 0), parsed as `T`. Empty fields are `None`.
+    pub fn field<T: FromStr>(&self, index: usize) -> Option<T> {
+        let field = self.fields.get(index)?.trim();
+        if field.is_empty() {
+            return None;
+        }
+        field.parse().ok()
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use super::quantize;
+use super::Texture;
+use crate::EQFilesError;
+
+const FILE_HEADER_SIZE: usize = 14;
+const INFO_HEADER_SIZE: usize = 40;
+
+/// Uncompressed Windows bitmaps, either palettized (1, 2, 4 or 8 bits) or true colour (24 or
+/// 32 bits, stored BGR).
+pub(super) fn decode(bytes: &[u8], masked: bool) -> Result<Texture, EQFilesError> {
+    let u16_at = |offset: usize| {
+        bytes
+            .get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .ok_or(EQFilesError::InvalidTexture("truncated BMP header"))
+    };
+    let u32_at = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(EQFilesError::InvalidTexture("truncated BMP header"))
+    };
+
+    let data_offset = u32_at(10)? as usize;
+    let info_size = u32_at(FILE_HEADER_SIZE)? as usize;
+    let width = u32_at(FILE_HEADER_SIZE + 4)? as i32;
+    let height = u32_at(FILE_HEADER_SIZE + 8)? as i32;
+    let bits = u16_at(FILE_HEADER_SIZE + 14)? as usize;
+    let compression = u32_at(FILE_HEADER_SIZE + 16)?;
+    let colours_used = u32_at(FILE_HEADER_SIZE + 32)? as usize;
+
+    // 3 is BI_BITFIELDS, which EQ only uses with the default 32 bit masks
+    if compression != 0 && !(compression == 3 && bits == 32) {
+        return Err(EQFilesError::InvalidTexture("compressed BMP"));
+    }
+    if width <= 0 || height == 0 {
+        return Err(EQFilesError::InvalidTexture("empty BMP"));
+    }
+    // a negative height means the rows are stored top first
+    let top_down = height < 0;
+    let (width, height) = (width as usize, height.unsigned_abs() as usize);
+
+    let palette = match bits {
+        1 | 2 | 4 | 8 => {
+            let count = if colours_used == 0 {
+                1 << bits
+            } else {
+                colours_used
+            };
+            let start = FILE_HEADER_SIZE + info_size;
+            let palette = count
+                .checked_mul(4)
+                .and_then(|length| bytes.get(start..start.checked_add(length)?))
+                .ok_or(EQFilesError::InvalidTexture("truncated BMP palette"))?;
+            palette
+                .chunks_exact(4)
+                .map(|bgrx| [bgrx[2], bgrx[1], bgrx[0], 255])
+                .collect::<Vec<_>>()
+        }
+        24 | 32 => Vec::new(),
+        _ => return Err(EQFilesError::InvalidTexture("unsupported BMP bit depth")),
+    };
+
+    let stride = width
+        .checked_mul(bits)
+        .ok_or(EQFilesError::InvalidTexture("BMP too large"))?
+        .div_ceil(32)
+        * 4;
+    // the pixels have to be there before anything is allocated for them
+    let pixels = stride
+        .checked_mul(height)
+        .and_then(|length| bytes.get(data_offset..data_offset.checked_add(length)?))
+        .ok_or(EQFilesError::InvalidTexture("truncated BMP pixels"))?;
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = if top_down { y } else { height - 1 - y };
+        let row = &pixels[row * stride..(row + 1) * stride];
+        for x in 0..width {
+            let pixel = match bits {
+                24 | 32 => {
+                    let bgr = &row[x * bits / 8..];
+                    [bgr[2], bgr[1], bgr[0], 255]
+                }
+                _ => {
+                    let bit = x * bits;
+                    let index = (row[bit / 8] >> (8 - bits - bit % 8)) & ((1 << bits) - 1) as u8;
+                    if masked && index == 0 {
+                        [0, 0, 0, 0]
+                    } else {
+                        palette
+                            .get(index as usize)
+                            .copied()
+                            .unwrap_or([0, 0, 0, 255])
+                    }
+                }
+            };
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+
+    Ok(Texture {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+/// An 8 bit palettized bitmap. Palette entry 0 is reserved for transparent pixels so the
+/// result works as a masked texture; the other 255 are chosen by the quantizer.
+pub(super) fn encode(texture: &Texture) -> Vec<u8> {
+    let (width, height) = (texture.width as usize, texture.height as usize);
+    let mut palette = vec![[0, 0, 0]];
+    palette.extend(quantize::palette(&texture.rgba, 255));
+
+    let mut cache = HashMap::new();
+    let indices = texture
+        .rgba
+        .chunks_exact(4)
+        .map(|pixel| {
+            if pixel[3] < quantize::ALPHA_THRESHOLD {
+                return 0;
+            }
+            let colour = [pixel[0], pixel[1], pixel[2]];
+            *cache
+                .entry(colour)
+                .or_insert_with(|| quantize::nearest(&palette[1..], colour) as u8 + 1)
+        })
+        .collect::<Vec<_>>();
+
+    let stride = width.div_ceil(4) * 4;
+    let data_offset = FILE_HEADER_SIZE + INFO_HEADER_SIZE + 256 * 4;
+    let file_size = data_offset + stride * height;
+
+    let mut result = Vec::with_capacity(file_size);
+    result.extend_from_slice(b"BM");
+    result.extend_from_slice(&(file_size as u32).to_le_bytes());
+    result.extend_from_slice(&0u32.to_le_bytes());
+    result.extend_from_slice(&(data_offset as u32).to_le_bytes());
+
+    result.extend_from_slice(&(INFO_HEADER_SIZE as u32).to_le_bytes());
+    result.extend_from_slice(&(width as i32).to_le_bytes());
+    result.extend_from_slice(&(height as i32).to_le_bytes());
+    result.extend_from_slice(&1u16.to_le_bytes());
+    result.extend_from_slice(&8u16.to_le_bytes());
+    result.extend_from_slice(&0u32.to_le_bytes());
+    result.extend_from_slice(&((stride * height) as u32).to_le_bytes());
+    result.extend_from_slice(&[0; 8]);
+    result.extend_from_slice(&256u32.to_le_bytes());
+    result.extend_from_slice(&0u32.to_le_bytes());
+
+    for index in 0..256 {
+        let [r, g, b] = palette.get(index).copied().unwrap_or_default();
+        result.extend_from_slice(&[b, g, r, 0]);
+    }
+    for y in (0..height).rev() {
+        result.extend_from_slice(&indices[y * width..(y + 1) * width]);
+        result.resize(result.len() + stride - width, 0);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_bits(bits: u16) -> Vec<u8> {
+        let texture = Texture {
+            width: 2,
+            height: 2,
+            rgba: vec![255; 16],
+        };
+        let mut bytes = encode(&texture);
+        bytes[FILE_HEADER_SIZE + 14..FILE_HEADER_SIZE + 16].copy_from_slice(&bits.to_le_bytes());
+        bytes
+    }
+
+    /// A bitmap stored bottom row first, from rows given top first and already packed.
+    fn bmp(width: i32, bits: u16, palette: &[[u8; 3]], rows: &[&[u8]]) -> Vec<u8> {
+        let stride = (width as usize * bits as usize).div_ceil(32) * 4;
+        let data_offset = FILE_HEADER_SIZE + INFO_HEADER_SIZE + palette.len() * 4;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"BM");
+        bytes.extend_from_slice(&((data_offset + stride * rows.len()) as u32).to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&(data_offset as u32).to_le_bytes());
+        bytes.extend_from_slice(&(INFO_HEADER_SIZE as u32).to_le_bytes());
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&(rows.len() as i32).to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.extend_from_slice(&(palette.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        for [r, g, b] in palette {
+            bytes.extend_from_slice(&[*b, *g, *r, 0]);
+        }
+        for row in rows.iter().rev() {
+            bytes.extend_from_slice(row);
+            bytes.resize(bytes.len() + stride - row.len(), 0);
+        }
+        bytes
+    }
+
+    const RED: [u8; 3] = [255, 0, 0];
+    const GREEN: [u8; 3] = [0, 255, 0];
+    const BLUE: [u8; 3] = [0, 0, 255];
+
+    #[test]
+    fn decodes_1_bit() {
+        let bytes = bmp(2, 1, &[RED, BLUE], &[&[0b1000_0000], &[0b0100_0000]]);
+        let texture = decode(&bytes, false).unwrap();
+        assert_eq!((texture.width, texture.height), (2, 2));
+        #[rustfmt::skip]
+        assert_eq!(texture.rgba, [
+            0, 0, 255, 255,  255, 0, 0, 255,
+            255, 0, 0, 255,  0, 0, 255, 255,
+        ]);
+    }
+
+    #[test]
+    fn decodes_4_bit() {
+        let bytes = bmp(3, 4, &[RED, GREEN, BLUE], &[&[0x12, 0x00], &[0x20, 0x10]]);
+        let texture = decode(&bytes, false).unwrap();
+        assert_eq!((texture.width, texture.height), (3, 2));
+        #[rustfmt::skip]
+        assert_eq!(texture.rgba, [
+            0, 255, 0, 255,  0, 0, 255, 255,  255, 0, 0, 255,
+            0, 0, 255, 255,  255, 0, 0, 255,  0, 255, 0, 255,
+        ]);
+    }
+
+    #[test]
+    fn decodes_8_bit_masked() {
+        let bytes = bmp(2, 8, &[RED, GREEN, BLUE], &[&[0, 1], &[2, 0]]);
+        let texture = decode(&bytes, true).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(texture.rgba, [
+            0, 0, 0, 0,  0, 255, 0, 255,
+            0, 0, 255, 255,  0, 0, 0, 0,
+        ]);
+
+        let texture = decode(&bytes, false).unwrap();
+        assert_eq!(texture.rgba[..4], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn decodes_24_bit() {
+        let bytes = bmp(
+            2,
+            24,
+            &[],
+            &[&[0, 0, 255, 0, 255, 0], &[255, 0, 0, 10, 20, 30]],
+        );
+        let texture = decode(&bytes, false).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(texture.rgba, [
+            255, 0, 0, 255,  0, 255, 0, 255,
+            0, 0, 255, 255,  30, 20, 10, 255,
+        ]);
+    }
+
+    #[test]
+    fn decodes_top_down() {
+        let mut bytes = bmp(2, 8, &[RED, GREEN, BLUE], &[&[0, 1], &[2, 0]]);
+        bytes[FILE_HEADER_SIZE + 8..FILE_HEADER_SIZE + 12].copy_from_slice(&(-2i32).to_le_bytes());
+        let texture = decode(&bytes, false).unwrap();
+        // the rows are now read in the order they are stored, bottom row of the input first
+        #[rustfmt::skip]
+        assert_eq!(texture.rgba, [
+            0, 0, 255, 255,  255, 0, 0, 255,
+            255, 0, 0, 255,  0, 255, 0, 255,
+        ]);
+    }
+
+    #[test]
+    fn rejects_sizes_larger_than_the_file() {
+        let mut bytes = bmp(2, 8, &[RED, GREEN, BLUE], &[&[0, 1], &[2, 0]]);
+        bytes[FILE_HEADER_SIZE + 4..FILE_HEADER_SIZE + 12]
+            .copy_from_slice(&[0xff, 0xff, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f]);
+        assert!(matches!(
+            decode(&bytes, false),
+            Err(EQFilesError::InvalidTexture("truncated BMP pixels"))
+        ));
+    }
+
+    #[test]
+    fn decodes_palettized_depths() {
+        for bits in [1, 2, 4, 8] {
+            assert!(decode(&with_bits(bits), false).is_ok(), "{bits} bits");
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_bit_depth() {
+        for bits in [0, 3, 5, 7, 16] {
+            assert!(
+                matches!(
+                    decode(&with_bits(bits), false),
+                    Err(EQFilesError::InvalidTexture("unsupported BMP bit depth"))
+                ),
+                "{bits} bits"
+            );
+        }
+    }
+}
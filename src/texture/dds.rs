@@ -0,0 +1,400 @@
+use super::quantize;
+use super::DxtFormat;
+use super::Texture;
+use crate::EQFilesError;
+
+const HEADER_SIZE: usize = 128;
+const HEADER_CAPS: u32 = 0x1;
+const HEADER_HEIGHT: u32 = 0x2;
+const HEADER_WIDTH: u32 = 0x4;
+const HEADER_PIXEL_FORMAT: u32 = 0x1000;
+const HEADER_LINEAR_SIZE: u32 = 0x80000;
+const CAPS_TEXTURE: u32 = 0x1000;
+const PIXEL_FORMAT_FOURCC: u32 = 0x4;
+const PIXEL_FORMAT_RGB: u32 = 0x40;
+const PIXEL_FORMAT_ALPHA: u32 = 0x1;
+
+/// Decodes the alpha half of a 16 byte DXT block.
+type AlphaBlock = fn(&[u8]) -> [u8; 16];
+
+/// DirectDraw surfaces, either DXT1/3/5 compressed or uncompressed with bit masks. Only the
+/// first mipmap level is decoded.
+pub(super) fn decode(bytes: &[u8]) -> Result<Texture, EQFilesError> {
+    let u32_at = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(EQFilesError::InvalidTexture("truncated DDS header"))
+    };
+
+    let height = u32_at(12)? as usize;
+    let width = u32_at(16)? as usize;
+    let format_flags = u32_at(80)?;
+    let four_cc = bytes.get(84..88).unwrap_or_default();
+    let data = &bytes[HEADER_SIZE.min(bytes.len())..];
+
+    let rgba = if format_flags & PIXEL_FORMAT_FOURCC != 0 {
+        let (block_size, alpha): (usize, AlphaBlock) = match four_cc {
+            b"DXT1" => (8, |_| [255; 16]),
+            b"DXT2" | b"DXT3" => (16, explicit_alpha),
+            b"DXT4" | b"DXT5" => (16, interpolated_alpha),
+            _ => return Err(EQFilesError::InvalidTexture("unsupported DDS compression")),
+        };
+        let blocks_wide = width.div_ceil(4);
+        let blocks_high = height.div_ceil(4);
+        // the blocks have to be there before anything is allocated for them
+        if blocks_wide
+            .checked_mul(blocks_high)
+            .and_then(|blocks| blocks.checked_mul(block_size))
+            .is_none_or(|length| length > data.len())
+        {
+            return Err(EQFilesError::InvalidTexture("truncated DDS pixels"));
+        }
+
+        let mut rgba = vec![0; width * height * 4];
+        for (index, block) in data
+            .chunks_exact(block_size)
+            .take(blocks_wide * blocks_high)
+            .enumerate()
+        {
+            let colours = colour_block(&block[block_size - 8..], block_size == 8);
+            let alphas = alpha(block);
+            let (block_x, block_y) = (index % blocks_wide * 4, index / blocks_wide * 4);
+            for i in 0..16 {
+                let (x, y) = (block_x + i % 4, block_y + i / 4);
+                if x < width && y < height {
+                    let mut pixel = colours[i];
+                    pixel[3] = pixel[3].min(alphas[i]);
+                    rgba[(y * width + x) * 4..][..4].copy_from_slice(&pixel);
+                }
+            }
+        }
+        rgba
+    } else if format_flags & PIXEL_FORMAT_RGB != 0 {
+        let bits = u32_at(88)? as usize;
+        let alpha_mask = if format_flags & PIXEL_FORMAT_ALPHA != 0 {
+            u32_at(104)?
+        } else {
+            0
+        };
+        let masks = [u32_at(92)?, u32_at(96)?, u32_at(100)?, alpha_mask];
+        let bytes_per_pixel = bits / 8;
+        if !matches!(bytes_per_pixel, 2..=4) {
+            return Err(EQFilesError::InvalidTexture("unsupported DDS bit depth"));
+        }
+        let stride = (width * bytes_per_pixel).div_ceil(4) * 4;
+        if stride
+            .checked_mul(height)
+            .is_none_or(|length| length > data.len())
+        {
+            return Err(EQFilesError::InvalidTexture("truncated DDS pixels"));
+        }
+
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let start = y * stride + x * bytes_per_pixel;
+                let mut value = [0; 4];
+                value[..bytes_per_pixel].copy_from_slice(&data[start..start + bytes_per_pixel]);
+                let value = u32::from_le_bytes(value);
+                for mask in masks {
+                    rgba.push(if mask == 0 { 255 } else { channel(value, mask) });
+                }
+            }
+        }
+        rgba
+    } else {
+        return Err(EQFilesError::InvalidTexture("unsupported DDS pixel format"));
+    };
+
+    Ok(Texture {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+/// Extracts the bits under `mask` and scales them to 8 bits.
+fn channel(value: u32, mask: u32) -> u8 {
+    let max = mask >> mask.trailing_zeros();
+    (((value & mask) >> mask.trailing_zeros()) * 255 / max) as u8
+}
+
+fn rgb565(value: u16) -> [u8; 4] {
+    let value = value as u32;
+    [
+        channel(value, 0xF800),
+        channel(value, 0x07E0),
+        channel(value, 0x001F),
+        255,
+    ]
+}
+
+/// The 8 byte colour block shared by every DXT format. Only DXT1 can use its three colour
+/// mode, where the last colour is transparent black.
+fn colour_block(block: &[u8], allow_transparent: bool) -> [[u8; 4]; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let palette = colour_palette(c0, c1, allow_transparent);
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    std::array::from_fn(|i| palette[(indices >> (i * 2)) as usize & 3])
+}
+
+/// Four colours when `c0 > c1` (or transparency isn't allowed), otherwise three plus
+/// transparent black.
+fn colour_palette(c0: u16, c1: u16, allow_transparent: bool) -> [[u8; 4]; 4] {
+    let (a, b) = (rgb565(c0), rgb565(c1));
+    let mix = |wa: u16, wb: u16| {
+        let total = wa + wb;
+        let mut pixel = [255; 4];
+        for i in 0..3 {
+            pixel[i] = ((a[i] as u16 * wa + b[i] as u16 * wb) / total) as u8;
+        }
+        pixel
+    };
+    if c0 > c1 || !allow_transparent {
+        [a, b, mix(2, 1), mix(1, 2)]
+    } else {
+        [a, b, mix(1, 1), [0, 0, 0, 0]]
+    }
+}
+
+/// DXT3: sixteen 4 bit alpha values.
+fn explicit_alpha(block: &[u8]) -> [u8; 16] {
+    std::array::from_fn(|i| ((block[i / 2] >> (i % 2 * 4)) & 0xF) * 17)
+}
+
+/// DXT5: two endpoints and sixteen 3 bit indices between them.
+fn interpolated_alpha(block: &[u8]) -> [u8; 16] {
+    let palette = alpha_palette(block[0], block[1]);
+    let mut indices = [0u8; 8];
+    indices[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(indices);
+    std::array::from_fn(|i| palette[(indices >> (i * 3)) as usize & 7])
+}
+
+/// Eight interpolated values when `a0 > a1`, otherwise six plus fully transparent and
+/// fully opaque.
+fn alpha_palette(a0: u8, a1: u8) -> [u8; 8] {
+    let (a0, a1) = (a0 as u32, a1 as u32);
+    std::array::from_fn(|i| match i {
+        0 => a0 as u8,
+        1 => a1 as u8,
+        _ if a0 > a1 => ((a0 * (8 - i as u32) + a1 * (i as u32 - 1)) / 7) as u8,
+        6 => 0,
+        7 => 255,
+        _ => ((a0 * (6 - i as u32) + a1 * (i as u32 - 1)) / 5) as u8,
+    })
+}
+
+/// A DDS without mipmaps. Pixels with alpha below the quantizer's threshold become DXT1's
+/// transparent black.
+pub(super) fn encode(texture: &Texture, format: DxtFormat) -> Vec<u8> {
+    let (width, height) = (texture.width as usize, texture.height as usize);
+    let (blocks_wide, blocks_high) = (width.div_ceil(4), height.div_ceil(4));
+    let block_size = match format {
+        DxtFormat::Dxt1 => 8,
+        DxtFormat::Dxt3 | DxtFormat::Dxt5 => 16,
+    };
+    let linear_size = blocks_wide * blocks_high * block_size;
+
+    let mut result = Vec::with_capacity(HEADER_SIZE + linear_size);
+    let mut push = |value: u32| result.extend_from_slice(&value.to_le_bytes());
+    push(u32::from_le_bytes(*b"DDS "));
+    push(124);
+    push(HEADER_CAPS | HEADER_HEIGHT | HEADER_WIDTH | HEADER_PIXEL_FORMAT | HEADER_LINEAR_SIZE);
+    push(height as u32);
+    push(width as u32);
+    push(linear_size as u32);
+    (0..13).for_each(|_| push(0)); // depth, mipmap count, reserved
+    push(32);
+    push(PIXEL_FORMAT_FOURCC);
+    push(u32::from_le_bytes(match format {
+        DxtFormat::Dxt1 => *b"DXT1",
+        DxtFormat::Dxt3 => *b"DXT3",
+        DxtFormat::Dxt5 => *b"DXT5",
+    }));
+    (0..5).for_each(|_| push(0)); // bit count and masks
+    push(CAPS_TEXTURE);
+    (0..4).for_each(|_| push(0)); // caps 2 to 4, reserved
+
+    for block_y in 0..blocks_high {
+        for block_x in 0..blocks_wide {
+            // edge blocks repeat the last row and column
+            let pixels: [[u8; 4]; 16] = std::array::from_fn(|i| {
+                let x = (block_x * 4 + i % 4).min(width - 1);
+                let y = (block_y * 4 + i / 4).min(height - 1);
+                let start = (y * width + x) * 4;
+                texture.rgba[start..start + 4].try_into().unwrap()
+            });
+            match format {
+                DxtFormat::Dxt1 => {
+                    result.extend_from_slice(&encode_colour_block(&pixels, true));
+                }
+                DxtFormat::Dxt3 => {
+                    for pair in pixels.chunks_exact(2) {
+                        result.push((pair[0][3] >> 4) | (pair[1][3] & 0xF0));
+                    }
+                    result.extend_from_slice(&encode_colour_block(&pixels, false));
+                }
+                DxtFormat::Dxt5 => {
+                    result.extend_from_slice(&encode_alpha_block(&pixels));
+                    result.extend_from_slice(&encode_colour_block(&pixels, false));
+                }
+            }
+        }
+    }
+    result
+}
+
+fn to_rgb565(colour: [u8; 3]) -> u16 {
+    ((colour[0] as u16 >> 3) << 11) | ((colour[1] as u16 >> 2) << 5) | (colour[2] as u16 >> 3)
+}
+
+/// Picks the endpoints at either end of the block's widest colour axis, then the closest of
+/// the interpolated colours for every pixel.
+fn encode_colour_block(pixels: &[[u8; 4]; 16], allow_transparent: bool) -> [u8; 8] {
+    let transparent = |pixel: &[u8; 4]| allow_transparent && pixel[3] < quantize::ALPHA_THRESHOLD;
+    let opaque = pixels
+        .iter()
+        .filter(|pixel| !transparent(pixel))
+        .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect::<Vec<_>>();
+    let has_transparent = opaque.len() < 16;
+
+    // the two colours furthest apart are the endpoints, with at most 16 pixels this is cheap
+    let (high, low) = opaque
+        .iter()
+        .flat_map(|a| opaque.iter().map(move |b| (*a, *b)))
+        .max_by_key(|(a, b)| quantize::distance(*a, *b))
+        .unwrap_or_default();
+
+    let (mut c0, mut c1) = (to_rgb565(high), to_rgb565(low));
+    // four colour mode needs c0 > c1 and three colour mode c0 <= c1
+    if has_transparent == (c0 > c1) {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+    if !has_transparent && c0 == c1 {
+        c1 = c1.saturating_sub(1);
+        if c0 == c1 {
+            c0 += 1;
+        }
+    }
+
+    let mut block = [0u8; 8];
+    block[0..2].copy_from_slice(&c0.to_le_bytes());
+    block[2..4].copy_from_slice(&c1.to_le_bytes());
+    let palette = colour_palette(c0, c1, allow_transparent).map(|[r, g, b, _]| [r, g, b]);
+    let colours = if has_transparent { 3 } else { 4 };
+
+    let mut indices = 0u32;
+    for (i, pixel) in pixels.iter().enumerate() {
+        let index = if transparent(pixel) {
+            3
+        } else {
+            quantize::nearest(&palette[..colours], [pixel[0], pixel[1], pixel[2]]) as u32
+        };
+        indices |= index << (i * 2);
+    }
+    block[4..8].copy_from_slice(&indices.to_le_bytes());
+    block
+}
+
+/// DXT5 alpha using the eight value mode between the block's lowest and highest alpha.
+fn encode_alpha_block(pixels: &[[u8; 4]; 16]) -> [u8; 8] {
+    let a0 = pixels.iter().map(|pixel| pixel[3]).max().unwrap_or(255);
+    let a1 = pixels.iter().map(|pixel| pixel[3]).min().unwrap_or(255);
+    let palette = alpha_palette(a0, a1);
+    let mut block = [0u8; 8];
+    block[0] = a0;
+    block[1] = a1;
+
+    let mut indices = 0u64;
+    for (i, pixel) in pixels.iter().enumerate() {
+        let index = (0..8)
+            .min_by_key(|index| (palette[*index] as i32 - pixel[3] as i32).abs())
+            .unwrap_or(0);
+        indices |= (index as u64) << (i * 3);
+    }
+    block[2..8].copy_from_slice(&indices.to_le_bytes()[..6]);
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    /// An 8x8 checkerboard of red and blue, with `alpha` applied per pixel.
+    fn checkerboard(alpha: impl Fn(usize) -> u8) -> Texture {
+        let rgba = (0..64)
+            .flat_map(|i| {
+                let [r, g, b, _] = if (i % 8 + i / 8) % 2 == 0 { RED } else { BLUE };
+                [r, g, b, alpha(i)]
+            })
+            .collect();
+        Texture {
+            width: 8,
+            height: 8,
+            rgba,
+        }
+    }
+
+    fn round_trip(texture: &Texture, format: DxtFormat) -> Texture {
+        let decoded = decode(&encode(texture, format)).unwrap();
+        assert_eq!(
+            (decoded.width, decoded.height),
+            (texture.width, texture.height)
+        );
+        decoded
+    }
+
+    fn assert_close(actual: &[u8], expected: &[u8]) {
+        for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!(a.abs_diff(*e) <= 2, "byte {i}: {a} != {e}");
+        }
+    }
+
+    #[test]
+    fn rejects_sizes_larger_than_the_file() {
+        let mut bytes = encode(&checkerboard(|_| 255), DxtFormat::Dxt1);
+        bytes[12..20].copy_from_slice(&[0xff; 8]);
+        assert!(matches!(
+            decode(&bytes),
+            Err(EQFilesError::InvalidTexture("truncated DDS pixels"))
+        ));
+    }
+
+    #[test]
+    fn dxt1_round_trip() {
+        let texture = checkerboard(|_| 255);
+        assert_close(&round_trip(&texture, DxtFormat::Dxt1).rgba, &texture.rgba);
+    }
+
+    #[test]
+    fn dxt1_round_trip_keeps_transparent_pixels() {
+        let texture = checkerboard(|i| if i % 3 == 0 { 0 } else { 255 });
+        let decoded = round_trip(&texture, DxtFormat::Dxt1);
+        for (actual, expected) in decoded.rgba.chunks(4).zip(texture.rgba.chunks(4)) {
+            if expected[3] == 0 {
+                assert_eq!(actual[3], 0);
+            } else {
+                assert_close(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn dxt3_round_trip() {
+        let texture = checkerboard(|i| (i % 16) as u8 * 17);
+        assert_close(&round_trip(&texture, DxtFormat::Dxt3).rgba, &texture.rgba);
+    }
+
+    #[test]
+    fn dxt5_round_trip() {
+        let texture = checkerboard(|i| if i % 2 == 0 { 0 } else { 255 });
+        assert_close(&round_trip(&texture, DxtFormat::Dxt5).rgba, &texture.rgba);
+    }
+}
@@ -0,0 +1,63 @@
+mod bmp;
+mod dds;
+mod quantize;
+
+use crate::EQFilesError;
+
+/// Block compression used when writing a DDS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DxtFormat {
+    /// Opaque or one bit alpha.
+    Dxt1,
+    /// Sharp alpha.
+    Dxt3,
+    /// Smooth alpha.
+    Dxt5,
+}
+
+/// A decoded texture, stored top row first as 8-bit RGBA.
+#[derive(Clone, Debug)]
+pub struct Texture {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Texture {
+    /// Decodes a BMP or DDS texture. The format is told by its magic rather than its
+    /// extension, as some DDS files are shipped named `.bmp`.
+    ///
+    /// When `masked` is set, as it is for [crate::BlendMode::Masked] materials, pixels using
+    /// the first palette entry are made fully transparent.
+    pub fn decode(bytes: &[u8], masked: bool) -> Result<Self, EQFilesError> {
+        match bytes.get(0..4) {
+            Some(b"DDS ") => dds::decode(bytes),
+            Some([b'B', b'M', ..]) => bmp::decode(bytes, masked),
+            _ => Err(EQFilesError::InvalidTexture("unknown texture format")),
+        }
+    }
+
+    /// Encodes the texture as PNG, e.g. for [crate::gltf::meshes_to_glb].
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        let mut encoder = png::Encoder::new(&mut result, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgba))
+            .expect("writing a PNG to memory cannot fail");
+        result
+    }
+
+    /// Encodes the texture as an 8 bit palettized BMP, the format EQ expects for masked
+    /// textures: transparent pixels use palette entry 0.
+    pub fn to_bmp(&self) -> Vec<u8> {
+        bmp::encode(self)
+    }
+
+    /// Encodes the texture as a block compressed DDS.
+    pub fn to_dds(&self, format: DxtFormat) -> Vec<u8> {
+        dds::encode(self, format)
+    }
+}
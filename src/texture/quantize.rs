@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+/// Alpha below which a pixel is treated as transparent when palettizing.
+pub(super) const ALPHA_THRESHOLD: u8 = 128;
+
+/// Reduces `rgba` to at most `colours` entries with median cut, then refines the palette
+/// with a few rounds of k-means so each entry sits at the centre of the pixels mapped to
+/// it. Transparent pixels are left out.
+pub(super) fn palette(rgba: &[u8], colours: usize) -> Vec<[u8; 3]> {
+    let mut histogram = HashMap::<[u8; 3], u32>::new();
+    for pixel in rgba.chunks_exact(4) {
+        if pixel[3] >= ALPHA_THRESHOLD {
+            *histogram.entry([pixel[0], pixel[1], pixel[2]]).or_default() += 1;
+        }
+    }
+    let mut histogram = histogram.into_iter().collect::<Vec<_>>();
+    histogram.sort_unstable();
+    if histogram.len() <= colours {
+        return histogram.into_iter().map(|(colour, _)| colour).collect();
+    }
+
+    // each box is a range of `histogram`, split along its widest channel at the median
+    let mut boxes = Vec::with_capacity(colours);
+    boxes.push(0..histogram.len());
+    while boxes.len() < colours {
+        let Some((index, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, range)| range.len() > 1)
+            .map(|(index, range)| {
+                let (channel, extent) = widest_channel(&histogram[range.clone()]);
+                (index, channel, extent)
+            })
+            .max_by_key(|(_, _, extent)| *extent)
+            .map(|(index, channel, _)| (index, channel))
+        else {
+            break;
+        };
+        let range = boxes.swap_remove(index);
+        let entries = &mut histogram[range.clone()];
+        entries.sort_unstable_by_key(|(colour, _)| colour[channel]);
+        let total = entries.iter().map(|(_, count)| *count as u64).sum::<u64>();
+        let mut seen = 0;
+        let median = entries
+            .iter()
+            .position(|(_, count)| {
+                seen += *count as u64;
+                seen * 2 >= total
+            })
+            .unwrap_or(0)
+            .clamp(0, entries.len() - 2);
+        boxes.push(range.start..range.start + median + 1);
+        boxes.push(range.start + median + 1..range.end);
+    }
+
+    let mut palette = boxes
+        .iter()
+        .map(|range| mean(histogram[range.clone()].iter().map(|(c, n)| (*c, *n))))
+        .collect::<Vec<_>>();
+    for _ in 0..4 {
+        let mut clusters = vec![Vec::new(); palette.len()];
+        for (colour, count) in &histogram {
+            clusters[nearest(&palette, *colour)].push((*colour, *count));
+        }
+        for (entry, cluster) in palette.iter_mut().zip(clusters) {
+            if !cluster.is_empty() {
+                *entry = mean(cluster.into_iter());
+            }
+        }
+    }
+    palette
+}
+
+/// Index of the entry of `palette` closest to `colour`.
+pub(super) fn nearest(palette: &[[u8; 3]], colour: [u8; 3]) -> usize {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, entry)| distance(**entry, colour))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+pub(super) fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    (0..3)
+        .map(|i| (a[i] as i32 - b[i] as i32).pow(2) as u32)
+        .sum()
+}
+
+fn widest_channel(entries: &[([u8; 3], u32)]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let values = entries.iter().map(|(colour, _)| colour[channel]);
+            let extent = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+            (channel, extent)
+        })
+        .max_by_key(|(_, extent)| *extent)
+        .unwrap_or((0, 0))
+}
+
+fn mean(entries: impl Iterator<Item = ([u8; 3], u32)>) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    let mut total = 0u64;
+    for (colour, count) in entries {
+        for i in 0..3 {
+            sum[i] += colour[i] as u64 * count as u64;
+        }
+        total += count as u64;
+    }
+    sum.map(|channel| (channel + total / 2).checked_div(total).unwrap_or(0) as u8)
+}
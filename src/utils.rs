@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use bytes::Buf;
 use bytes::Bytes;
+use glam::Vec3;
 
 use crate::EQFilesError;
 
@@ -35,6 +36,23 @@ pub fn take(input: &mut Bytes, count: usize) -> Bytes {
     input.copy_to_bytes(count)
 }
 
+pub(crate) fn read_vec3(input: &mut Bytes) -> Vec3 {
+    Vec3::new(input.get_f32_le(), input.get_f32_le(), input.get_f32_le())
+}
+
+/// Fails with [EQFilesError::Truncated] unless `count` records of `size` bytes remain.
+pub(crate) fn check_remaining(
+    input: &Bytes,
+    count: usize,
+    size: usize,
+    what: &'static str,
+) -> Result<(), EQFilesError> {
+    match count.checked_mul(size) {
+        Some(length) if length <= input.remaining() => Ok(()),
+        _ => Err(EQFilesError::Truncated(what)),
+    }
+}
+
 pub fn decode_string(buffer: &mut Bytes, length: usize) -> Result<String, EQFilesError> {
     let mut result = Vec::new();
     for i in 0..length {
@@ -48,3 +66,10 @@ pub fn decode_string(buffer: &mut Bytes, length: usize) -> Result<String, EQFile
         Err(e) => Err(EQFilesError::ErrorDecodingString(e)),
     }
 }
+
+/// EQ is left handed with Z up, most tools are right handed with Y up. Swapping Y and Z
+/// converts between them, and being a mirror it also turns EQ's clockwise faces
+/// counter-clockwise.
+pub(crate) fn to_y_up(v: [f32; 3]) -> [f32; 3] {
+    [v[0], v[2], v[1]]
+}
@@ -3,20 +3,24 @@ mod t04_04_texture_bitmap_info;
 mod t05_05_texture_bitmap_info_ref;
 mod t06_06_fragment6;
 mod t07_07_fragment7;
+mod t08_08_camera;
+mod t09_09_camera_ref;
 mod t16_10_skeleton_track_set;
 mod t17_11_skeleton_track_set_ref;
 mod t18_12_skeleton_piece_track_def;
 mod t19_13_skeleton_piece_track;
 mod t20_14_model;
-mod t23_17_fragment23;
-mod t24_18_fragment24;
+mod t21_15_object_location;
+mod t23_17_polyhedron_definition;
+mod t24_18_polyhedron_definition_ref;
 mod t38_26_particle_sprite;
 mod t39_27_particle_sprite_ref;
-mod t44_2c_fragment44;
+mod t44_2c_legacy_mesh;
 mod t45_2d_mesh_ref;
 mod t48_30_material;
 mod t49_31_material_list;
 mod t52_34_particle_cloud;
+mod t53_35_global_ambient_light;
 mod t54_36_mesh;
 
 use std::sync::Arc;
@@ -26,21 +30,39 @@ pub use t04_04_texture_bitmap_info::WldTextureBitmapInfo;
 pub use t05_05_texture_bitmap_info_ref::WldTextureBitmapInfoRef;
 pub use t06_06_fragment6::WldFragment6;
 pub use t07_07_fragment7::WldFragment7;
+pub use t08_08_camera::WldCamera;
+pub use t08_08_camera::WldCameraBspNode;
+pub use t08_08_camera::WldCameraFlags;
+pub use t08_08_camera::WldRenderInfo;
+pub use t08_08_camera::WldRenderInfoFlags;
+pub use t09_09_camera_ref::WldCameraRef;
+pub use t16_10_skeleton_track_set::WldSkeletonDag;
 pub use t16_10_skeleton_track_set::WldSkeletonTrackSet;
 pub use t17_11_skeleton_track_set_ref::WldSkeletonTrackSetRef;
 pub use t18_12_skeleton_piece_track_def::WldSkeletonPieceTrackDef;
+pub use t18_12_skeleton_piece_track_def::WldSkeletonPieceTrackDefFlags;
 pub use t19_13_skeleton_piece_track::WldSkeletonPieceTrack;
 pub use t20_14_model::WldModel;
-pub use t23_17_fragment23::WldFragment23;
-pub use t24_18_fragment24::WldFragment24;
+pub use t21_15_object_location::WldObjectLocation;
+pub use t23_17_polyhedron_definition::WldPolyhedronDefinition;
+pub use t23_17_polyhedron_definition::WldPolyhedronDefinitionFlags;
+pub use t24_18_polyhedron_definition_ref::WldPolyhedronDefinitionRef;
 pub use t38_26_particle_sprite::WldParticleSprite;
 pub use t39_27_particle_sprite_ref::WldParticleSpriteRef;
-pub use t44_2c_fragment44::WldFragment44;
+pub use t44_2c_legacy_mesh::WldLegacyMesh;
+pub use t44_2c_legacy_mesh::WldLegacyMeshFace;
+pub use t44_2c_legacy_mesh::WldLegacyMeshFlags;
 pub use t45_2d_mesh_ref::WldMeshRef;
+pub use t48_30_material::WldDrawStyle;
+pub use t48_30_material::WldFillMode;
+pub use t48_30_material::WldLighting;
 pub use t48_30_material::WldMaterial;
+pub use t48_30_material::WldRenderMethod;
 pub use t49_31_material_list::WldMaterialList;
 pub use t52_34_particle_cloud::WldParticleCloud;
+pub use t53_35_global_ambient_light::WldGlobalAmbientLight;
 pub use t54_36_mesh::WldMesh;
+pub use t54_36_mesh::WldSkinnedMesh;
 
 use super::header::WldHeader;
 use super::names::WldNames;
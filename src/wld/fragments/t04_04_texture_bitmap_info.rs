@@ -76,3 +76,53 @@ impl Decoder<Settings> for WldTextureBitmapInfo {
         })
     }
 }
+
+impl WldTextureBitmapInfo {
+    fn is_animated(&self) -> bool {
+        self.flags.animated() && self.sleep > 0 && self.texture_list.len() > 1
+    }
+
+    /// Frame shown first, `texture_current` when frames are skipped ahead.
+    fn first_frame(&self) -> usize {
+        if self.texture_list.is_empty() {
+            return 0;
+        }
+        let skip = if self.flags.animated() && self.flags.skip_frames() {
+            self.texture_current as usize
+        } else {
+            0
+        };
+        skip % self.texture_list.len()
+    }
+
+    /// Index into `texture_list` of the frame shown `elapsed_ms` after the texture appeared.
+    pub fn frame_at(&self, elapsed_ms: u32) -> usize {
+        if !self.is_animated() {
+            return self.first_frame();
+        }
+        (self.first_frame() + (elapsed_ms / self.sleep) as usize) % self.texture_list.len()
+    }
+
+    /// 0x03 fragment shown `elapsed_ms` after the texture appeared.
+    pub fn texture_at(&self, elapsed_ms: u32) -> Option<u32> {
+        self.texture_list.get(self.frame_at(elapsed_ms)).copied()
+    }
+
+    /// One full loop as `(start_ms, 0x03 fragment)` pairs, in the order they are shown.
+    pub fn frame_sequence(&self) -> Vec<(u32, u32)> {
+        let frames = if self.is_animated() {
+            self.texture_list.len()
+        } else {
+            self.texture_list.len().min(1)
+        };
+        (0..frames)
+            .map(|i| {
+                let start = i as u32 * self.sleep;
+                (
+                    start,
+                    self.texture_list[(self.first_frame() + i) % self.texture_list.len()],
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,188 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use bitbybit::bitfield;
+use bytes::Buf;
+use bytes::Bytes;
+
+use crate::utils::count;
+use crate::Decoder;
+use crate::Settings;
+use crate::WldFragment;
+
+/// A 3D sprite definition (`3DSPRITEDEF`), which zone files carry as their camera.
+#[derive(Clone, Debug)]
+pub struct WldCamera {
+    pub name: Option<String>,
+    pub flags: WldCameraFlags,
+    pub vertex_count: u32,
+    pub bsp_node_count: u32,
+    pub sphere_list_ref: u32,
+    pub centre_offset: Option<[f32; 3]>,
+    pub bounding_radius: Option<f32>,
+    pub vertices: Vec<[f32; 3]>,
+    pub bsp_nodes: Vec<WldCameraBspNode>,
+}
+
+#[bitfield(u32)]
+pub struct WldCameraFlags {
+    #[bit(0, r)]
+    pub has_centre_offset: bool, // 0x01
+    #[bit(1, r)]
+    pub has_bounding_radius: bool, // 0x02
+}
+
+#[derive(Clone, Debug)]
+pub struct WldCameraBspNode {
+    pub front_tree: u32,
+    pub back_tree: u32,
+    pub vertex_indices: Vec<u32>,
+    pub render_method: u32,
+    pub render_info: WldRenderInfo,
+}
+
+#[bitfield(u32)]
+pub struct WldRenderInfoFlags {
+    #[bit(0, r)]
+    pub has_pen: bool, // 0x01
+    #[bit(1, r)]
+    pub has_brightness: bool, // 0x02
+    #[bit(2, r)]
+    pub has_scaled_ambient: bool, // 0x04
+    #[bit(3, r)]
+    pub has_simple_sprite: bool, // 0x08
+    #[bit(4, r)]
+    pub has_uv_info: bool, // 0x10
+    #[bit(5, r)]
+    pub has_uv_map: bool, // 0x20
+}
+
+#[derive(Clone, Debug)]
+pub struct WldRenderInfo {
+    pub flags: WldRenderInfoFlags,
+    pub pen: Option<u32>,
+    pub brightness: Option<f32>,
+    pub scaled_ambient: Option<f32>,
+    pub simple_sprite_ref: Option<u32>,
+    pub uv_info: Option<([f32; 3], [f32; 3], [f32; 3])>,
+    pub uv_map: Vec<[f32; 2]>,
+}
+
+impl WldFragment for WldCamera {
+    const TYPE: u32 = 8;
+}
+
+impl Decoder<Settings> for WldCamera {
+    fn new(input: &mut Bytes, settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let name = settings.get_name();
+        let flags = WldCameraFlags::new_with_raw_value(input.get_u32_le());
+        let vertex_count = input.get_u32_le();
+        let bsp_node_count = input.get_u32_le();
+        let sphere_list_ref = input.get_u32_le();
+        let centre_offset = flags
+            .has_centre_offset()
+            .then(|| [input.get_f32_le(), input.get_f32_le(), input.get_f32_le()]);
+        let bounding_radius = flags.has_bounding_radius().then(|| input.get_f32_le());
+
+        let mut vertices = Vec::with_capacity(vertex_count as usize);
+        for _ in 0..vertex_count {
+            vertices.push([input.get_f32_le(), input.get_f32_le(), input.get_f32_le()]);
+        }
+
+        let bsp_nodes = count(
+            input,
+            bsp_node_count as usize,
+            settings,
+            WldCameraBspNode::new,
+        )?;
+
+        Ok(Self {
+            name,
+            flags,
+            vertex_count,
+            bsp_node_count,
+            sphere_list_ref,
+            centre_offset,
+            bounding_radius,
+            vertices,
+            bsp_nodes,
+        })
+    }
+}
+
+impl Decoder<Settings> for WldCameraBspNode {
+    fn new(input: &mut Bytes, _settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let vertex_count = input.get_u32_le();
+        let front_tree = input.get_u32_le();
+        let back_tree = input.get_u32_le();
+        let mut vertex_indices = Vec::with_capacity(vertex_count as usize);
+        for _ in 0..vertex_count {
+            vertex_indices.push(input.get_u32_le());
+        }
+        let render_method = input.get_u32_le();
+
+        let flags = WldRenderInfoFlags::new_with_raw_value(input.get_u32_le());
+        let pen = flags.has_pen().then(|| input.get_u32_le());
+        let brightness = flags.has_brightness().then(|| input.get_f32_le());
+        let scaled_ambient = flags.has_scaled_ambient().then(|| input.get_f32_le());
+        let simple_sprite_ref = flags.has_simple_sprite().then(|| input.get_u32_le());
+        let uv_info = flags.has_uv_info().then(|| {
+            (
+                [input.get_f32_le(), input.get_f32_le(), input.get_f32_le()],
+                [input.get_f32_le(), input.get_f32_le(), input.get_f32_le()],
+                [input.get_f32_le(), input.get_f32_le(), input.get_f32_le()],
+            )
+        });
+        let mut uv_map = Vec::new();
+        if flags.has_uv_map() {
+            for _ in 0..input.get_u32_le() {
+                uv_map.push([input.get_f32_le(), input.get_f32_le()]);
+            }
+        }
+
+        Ok(Self {
+            front_tree,
+            back_tree,
+            vertex_indices,
+            render_method,
+            render_info: WldRenderInfo {
+                flags,
+                pen,
+                brightness,
+                scaled_ambient,
+                simple_sprite_ref,
+                uv_info,
+                uv_map,
+            },
+        })
+    }
+}
+
+impl Debug for WldCameraFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WldCameraFlags")
+            .field("has_centre_offset", &self.has_centre_offset())
+            .field("has_bounding_radius", &self.has_bounding_radius())
+            .finish()
+    }
+}
+
+impl Debug for WldRenderInfoFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WldRenderInfoFlags")
+            .field("has_pen", &self.has_pen())
+            .field("has_brightness", &self.has_brightness())
+            .field("has_scaled_ambient", &self.has_scaled_ambient())
+            .field("has_simple_sprite", &self.has_simple_sprite())
+            .field("has_uv_info", &self.has_uv_info())
+            .field("has_uv_map", &self.has_uv_map())
+            .finish()
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use bytes::Buf;
+use bytes::Bytes;
+
+use crate::Decoder;
+use crate::Settings;
+use crate::WldFragment;
+
+#[derive(Clone, Debug)]
+pub struct WldCameraRef {
+    pub name: Option<String>,
+    pub reference: u32,
+    pub flags: u32,
+}
+
+impl WldFragment for WldCameraRef {
+    const TYPE: u32 = 9;
+}
+
+impl Decoder<Settings> for WldCameraRef {
+    fn new(input: &mut Bytes, settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let name = settings.get_name();
+
+        Ok(Self {
+            name,
+            reference: input.get_u32_le(),
+            flags: input.get_u32_le(),
+        })
+    }
+}
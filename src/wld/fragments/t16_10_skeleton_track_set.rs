@@ -70,12 +70,12 @@ impl Decoder<Settings> for WldSkeletonTrackSet {
 
         let mut dm_sprites = Vec::new();
         let mut link_skin_updates_to_dag_index = Vec::new();
-        if mesh_reference_count.is_some() {
-            for _ in 0..mesh_reference_count.unwrap() {
+        if let Some(mesh_reference_count) = mesh_reference_count {
+            for _ in 0..mesh_reference_count {
                 dm_sprites.push(input.get_u32_le());
             }
 
-            for _ in 0..mesh_reference_count.unwrap() {
+            for _ in 0..mesh_reference_count {
                 link_skin_updates_to_dag_index.push(input.get_u32_le());
             }
         }
@@ -86,9 +86,11 @@ impl Decoder<Settings> for WldSkeletonTrackSet {
                 dag.sub_dags.clone()
             };
 
+            // a sub index past the end of the dag list has no node to parent, so skip it
             for sub in &subs {
-                let sb = dags.get_mut(*sub as usize).unwrap();
-                sb.parent = Some(index as u32);
+                if let Some(sb) = dags.get_mut(*sub as usize) {
+                    sb.parent = Some(index as u32);
+                }
             }
         }
 
@@ -145,3 +147,50 @@ impl Debug for WldSkeletonFlags {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::wld::fragments::BaseSettings;
+    use crate::wld::raw_fragment::WldRawFragment;
+
+    fn decode(dags: &[&[u32]]) -> WldSkeletonTrackSet {
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(0);
+        buffer.put_u32_le(dags.len() as u32);
+        buffer.put_u32_le(0);
+        for sub_dags in dags {
+            buffer.put_i32_le(0);
+            buffer.put_u32_le(0);
+            buffer.put_u32_le(0);
+            buffer.put_u32_le(0);
+            buffer.put_u32_le(sub_dags.len() as u32);
+            sub_dags.iter().for_each(|sub| buffer.put_u32_le(*sub));
+        }
+        let settings = BaseSettings::default().make_settings(Arc::new(WldRawFragment {
+            fragment_size: 0,
+            fragment_type: WldSkeletonTrackSet::TYPE,
+            name_ref: 0,
+            name: None,
+            contents: Bytes::new(),
+        }));
+        WldSkeletonTrackSet::new(&mut buffer.freeze(), settings).unwrap()
+    }
+
+    #[test]
+    fn sub_dags_set_parent() {
+        let skeleton = decode(&[&[1, 2], &[], &[]]);
+        let parents: Vec<_> = skeleton.dags.iter().map(|dag| dag.parent).collect();
+        assert_eq!(parents, [None, Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn out_of_range_sub_dag_is_skipped() {
+        let skeleton = decode(&[&[1, 7], &[]]);
+        let parents: Vec<_> = skeleton.dags.iter().map(|dag| dag.parent).collect();
+        assert_eq!(parents, [None, Some(0)]);
+    }
+}
@@ -1,5 +1,9 @@
+use std::f32::consts::TAU;
+use std::fmt::Debug;
+use std::fmt::Formatter;
 use std::sync::Arc;
 
+use bitbybit::bitfield;
 use bytes::Buf;
 use bytes::Bytes;
 
@@ -11,9 +15,14 @@ use crate::WldFragment;
 pub struct WldSkeletonPieceTrackDef {
     pub name_ref: i32,
     pub name: Option<String>,
-    pub flags: u32,
+    pub flags: WldSkeletonPieceTrackDefFlags,
     pub frames: Vec<WldSkeletonPieceTrackFrameTransform>,
-    pub remainder: Bytes,
+}
+
+#[bitfield(u32)]
+pub struct WldSkeletonPieceTrackDefFlags {
+    #[bit(3, r)]
+    pub packed_frames: bool, // 0x08
 }
 
 impl WldFragment for WldSkeletonPieceTrackDef {
@@ -27,42 +36,15 @@ impl Decoder<Settings> for WldSkeletonPieceTrackDef {
     {
         let name_ref = settings.get_name_ref();
         let name = settings.get_name();
-        let flags = input.get_u32_le(); // bit 3 means more values
+        let flags = WldSkeletonPieceTrackDefFlags::new_with_raw_value(input.get_u32_le());
         let frame_count = input.get_u32_le();
-        let mut frames = Vec::new();
+        let mut frames = Vec::with_capacity(frame_count as usize);
         for _ in 0..frame_count {
-            let rotation_denominator = input.get_i16_le();
-            let rotation_x = input.get_i16_le();
-            let rotation_y = input.get_i16_le();
-            let rotation_z = input.get_i16_le();
-            let shift_x = input.get_i16_le();
-            let shift_y = input.get_i16_le();
-            let shift_z = input.get_i16_le();
-            let shift_denominator = input.get_i16_le();
-            let mut frame_transform = if shift_denominator != 0 {
-                WldSkeletonPieceTrackFrameTransform {
-                    translation: glam::vec3(
-                        shift_x as f32 / 256f32,
-                        shift_y as f32 / 256f32,
-                        shift_z as f32 / 256f32,
-                    ),
-                    scale: shift_denominator as f32 / 256f32,
-                    ..Default::default()
-                }
+            frames.push(if flags.packed_frames() {
+                WldSkeletonPieceTrackFrameTransform::packed(input)
             } else {
-                WldSkeletonPieceTrackFrameTransform {
-                    translation: glam::vec3(0.0, 0.0, 0.0),
-                    ..Default::default()
-                }
-            };
-            frame_transform.rotation = glam::quat(
-                rotation_x as f32,
-                rotation_y as f32,
-                rotation_z as f32,
-                rotation_denominator as f32,
-            )
-            .normalize();
-            frames.push(frame_transform);
+                WldSkeletonPieceTrackFrameTransform::legacy(input)
+            });
         }
 
         Ok(Self {
@@ -70,7 +52,6 @@ impl Decoder<Settings> for WldSkeletonPieceTrackDef {
             name,
             flags,
             frames,
-            remainder: input.clone(),
         })
     }
 }
@@ -79,20 +60,178 @@ impl Decoder<Settings> for WldSkeletonPieceTrackDef {
 pub struct WldSkeletonPieceTrackFrameTransform {
     pub translation: glam::Vec3,
     pub rotation: glam::Quat,
-    pub scale: f32,
     pub model_matrix: glam::Mat4,
 }
 
 impl WldSkeletonPieceTrackFrameTransform {
-    pub fn scale_matrix(&self) -> glam::Mat4 {
-        glam::Mat4::from_scale(glam::Vec3::from((self.scale, self.scale, self.scale)))
+    /// Eight `i16`: a quaternion as `w, x, y, z` numerators over a common denominator (which
+    /// is the `w` itself), then the `x, y, z` translation numerators and their denominator.
+    fn packed(input: &mut Bytes) -> Self {
+        let rotation_denominator = input.get_i16_le() as f32;
+        let rotation_x = input.get_i16_le() as f32;
+        let rotation_y = input.get_i16_le() as f32;
+        let rotation_z = input.get_i16_le() as f32;
+        let shift_x = input.get_i16_le() as f32;
+        let shift_y = input.get_i16_le() as f32;
+        let shift_z = input.get_i16_le() as f32;
+        let shift_denominator = input.get_i16_le() as f32;
+
+        let rotation = glam::quat(rotation_x, rotation_y, rotation_z, rotation_denominator);
+        Self {
+            translation: translation(shift_x, shift_y, shift_z, shift_denominator),
+            rotation: if rotation.length_squared() > 0.0 {
+                rotation.normalize()
+            } else {
+                glam::Quat::IDENTITY
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Eight `f32` in the same order as the packed layout, but the rotation is a set of euler
+    /// angles where the denominator amounts to a full turn.
+    fn legacy(input: &mut Bytes) -> Self {
+        let rotation_denominator = input.get_f32_le();
+        let rotation_x = input.get_f32_le();
+        let rotation_y = input.get_f32_le();
+        let rotation_z = input.get_f32_le();
+        let shift_x = input.get_f32_le();
+        let shift_y = input.get_f32_le();
+        let shift_z = input.get_f32_le();
+        let shift_denominator = input.get_f32_le();
+
+        let rotation = if rotation_denominator != 0.0 {
+            let angle = |value: f32| value / rotation_denominator * TAU;
+            glam::Quat::from_euler(
+                glam::EulerRot::ZYX,
+                angle(rotation_z),
+                angle(rotation_y),
+                angle(rotation_x),
+            )
+        } else {
+            glam::Quat::IDENTITY
+        };
+        Self {
+            translation: translation(shift_x, shift_y, shift_z, shift_denominator),
+            rotation,
+            ..Default::default()
+        }
     }
 
     pub fn rotation_matrix(&self) -> glam::Mat4 {
-        glam::Mat4::from_quat(glam::Quat::from_array(self.rotation.to_array()))
+        glam::Mat4::from_quat(self.rotation)
     }
 
     pub fn translation_matrix(&self) -> glam::Mat4 {
-        glam::Mat4::from_translation(self.translation.to_array().into())
+        glam::Mat4::from_translation(self.translation)
+    }
+
+    pub fn local_matrix(&self) -> glam::Mat4 {
+        self.translation_matrix() * self.rotation_matrix()
+    }
+
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+            ..Default::default()
+        }
+    }
+}
+
+fn translation(x: f32, y: f32, z: f32, denominator: f32) -> glam::Vec3 {
+    if denominator != 0.0 {
+        glam::vec3(x, y, z) / denominator
+    } else {
+        glam::Vec3::ZERO
+    }
+}
+
+impl Debug for WldSkeletonPieceTrackDefFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WldSkeletonPieceTrackDefFlags")
+            .field("packed_frames", &self.packed_frames())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    use bytes::BufMut;
+    use bytes::BytesMut;
+    use glam::vec3;
+
+    use super::*;
+    use crate::wld::fragments::BaseSettings;
+    use crate::wld::raw_fragment::WldRawFragment;
+
+    fn decode(flags: u32, frame: impl FnOnce(&mut BytesMut)) -> WldSkeletonPieceTrackDef {
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(flags);
+        buffer.put_u32_le(1);
+        frame(&mut buffer);
+        let settings = BaseSettings::default().make_settings(Arc::new(WldRawFragment {
+            fragment_size: 0,
+            fragment_type: WldSkeletonPieceTrackDef::TYPE,
+            name_ref: 0,
+            name: None,
+            contents: Bytes::new(),
+        }));
+        WldSkeletonPieceTrackDef::new(&mut buffer.freeze(), settings).unwrap()
+    }
+
+    fn packed(values: [i16; 8]) -> WldSkeletonPieceTrackFrameTransform {
+        decode(0x08, |buffer| {
+            values.iter().for_each(|v| buffer.put_i16_le(*v))
+        })
+        .frames
+        .remove(0)
+    }
+
+    fn legacy(values: [f32; 8]) -> WldSkeletonPieceTrackFrameTransform {
+        decode(0, |buffer| {
+            values.iter().for_each(|v| buffer.put_f32_le(*v))
+        })
+        .frames
+        .remove(0)
+    }
+
+    #[test]
+    fn packed_translation_uses_denominator() {
+        let frame = packed([16384, 0, 0, 0, 30, -12, 6, 3]);
+        assert!(frame.translation.abs_diff_eq(vec3(10.0, -4.0, 2.0), 1e-6));
+        assert_eq!(frame.rotation, glam::Quat::IDENTITY);
+    }
+
+    #[test]
+    fn packed_rotation_is_quaternion() {
+        // a quarter turn around z
+        let component = (FRAC_1_SQRT_2 * 16384.0).round() as i16;
+        let frame = packed([component, 0, 0, component, 0, 0, 0, 256]);
+        assert!((frame.rotation * glam::Vec3::X).abs_diff_eq(glam::Vec3::Y, 1e-4));
+    }
+
+    #[test]
+    fn packed_zero_denominators_are_identity() {
+        let frame = packed([0, 0, 0, 0, 100, 100, 100, 0]);
+        assert_eq!(frame.translation, glam::Vec3::ZERO);
+        assert_eq!(frame.rotation, glam::Quat::IDENTITY);
+    }
+
+    #[test]
+    fn legacy_rotation_is_euler() {
+        let frame = legacy([512.0, 0.0, 0.0, 128.0, 1.0, 2.0, 3.0, 1.0]);
+        assert!(frame.translation.abs_diff_eq(vec3(1.0, 2.0, 3.0), 1e-6));
+        assert!((frame.rotation * glam::Vec3::X).abs_diff_eq(glam::Vec3::Y, 1e-4));
+    }
+
+    #[test]
+    fn local_matrix_rotates_then_translates() {
+        let component = (FRAC_1_SQRT_2 * 16384.0).round() as i16;
+        let frame = packed([component, 0, 0, component, 256, 0, 0, 256]);
+        let point = frame.local_matrix().transform_point3(glam::Vec3::X);
+        assert!(point.abs_diff_eq(vec3(1.0, 1.0, 0.0), 1e-4));
     }
 }
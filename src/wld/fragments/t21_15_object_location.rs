@@ -0,0 +1,86 @@
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+use bytes::Buf;
+use bytes::Bytes;
+use glam::EulerRot;
+use glam::Mat4;
+use glam::Quat;
+use glam::Vec3;
+
+use crate::Decoder;
+use crate::Settings;
+use crate::WldFragment;
+
+/// A placed instance of a [crate::WldModel], as found in a zone's `objects.wld`.
+#[derive(Clone, Debug)]
+pub struct WldObjectLocation {
+    pub name_ref: i32,
+    pub name: Option<String>,
+    pub model_name_ref: i32,
+    /// Name of the 0x14 model placed, usually defined in another WLD, e.g. `TREE_ACTORDEF`.
+    pub model_name: Option<String>,
+    pub flags: u32,
+    pub sphere_ref: u32,
+    pub position: Vec3,
+    /// Rotation around Z, Y and X, where 512 is a full turn.
+    pub rotation: [f32; 3],
+    pub scale_y: f32,
+    pub scale_x: f32,
+    pub vertex_color_ref: u32,
+}
+
+impl WldFragment for WldObjectLocation {
+    const TYPE: u32 = 21;
+}
+
+impl Decoder<Settings> for WldObjectLocation {
+    fn new(input: &mut Bytes, settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let name_ref = settings.get_name_ref();
+        let name = settings.get_name();
+        let model_name_ref = input.get_i32_le();
+        let model_name = settings.get_from_name_ref(model_name_ref);
+        let flags = input.get_u32_le();
+        let sphere_ref = input.get_u32_le();
+        let position = Vec3::new(input.get_f32_le(), input.get_f32_le(), input.get_f32_le());
+        let rotation = [input.get_f32_le(), input.get_f32_le(), input.get_f32_le()];
+        let _unknown = input.get_f32_le();
+        let scale_y = input.get_f32_le();
+        let scale_x = input.get_f32_le();
+        let vertex_color_ref = input.get_u32_le();
+
+        Ok(Self {
+            name_ref,
+            name,
+            model_name_ref,
+            model_name,
+            flags,
+            sphere_ref,
+            position,
+            rotation,
+            scale_y,
+            scale_x,
+            vertex_color_ref,
+        })
+    }
+}
+
+impl WldObjectLocation {
+    /// Model to world space. Only two scales are stored, the Y one also applies to Z.
+    pub fn transform(&self) -> Mat4 {
+        let angle = |value: f32| value / 512.0 * TAU;
+        Mat4::from_scale_rotation_translation(
+            Vec3::new(self.scale_x, self.scale_y, self.scale_y),
+            Quat::from_euler(
+                EulerRot::ZYX,
+                angle(self.rotation[0]),
+                angle(self.rotation[1]),
+                angle(self.rotation[2]),
+            ),
+            self.position,
+        )
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use bitbybit::bitfield;
+use bytes::Buf;
+use bytes::Bytes;
+
+use crate::Decoder;
+use crate::Settings;
+use crate::WldFragment;
+
+#[derive(Clone, Debug)]
+pub struct WldPolyhedronDefinition {
+    pub name: Option<String>,
+    pub flags: WldPolyhedronDefinitionFlags,
+    pub vertex_count: u32,
+    pub face_count: u32,
+    pub bounding_radius: f32,
+    pub scale_factor: Option<f32>,
+    pub vertices: Vec<[f32; 3]>,
+    pub faces: Vec<Vec<u32>>,
+}
+
+#[bitfield(u32)]
+pub struct WldPolyhedronDefinitionFlags {
+    #[bit(0, r)]
+    pub has_scale_factor: bool, // 0x01
+}
+
+impl WldFragment for WldPolyhedronDefinition {
+    const TYPE: u32 = 23;
+}
+
+impl Decoder<Settings> for WldPolyhedronDefinition {
+    fn new(input: &mut Bytes, settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let name = settings.get_name();
+        let flags = WldPolyhedronDefinitionFlags::new_with_raw_value(input.get_u32_le());
+        let vertex_count = input.get_u32_le();
+        let face_count = input.get_u32_le();
+        let bounding_radius = input.get_f32_le();
+        let scale_factor = flags.has_scale_factor().then(|| input.get_f32_le());
+
+        let mut vertices = Vec::with_capacity(vertex_count as usize);
+        for _ in 0..vertex_count {
+            vertices.push([input.get_f32_le(), input.get_f32_le(), input.get_f32_le()]);
+        }
+
+        // each face is a polygon, not necessarily a triangle
+        let mut faces = Vec::with_capacity(face_count as usize);
+        for _ in 0..face_count {
+            let face_vertex_count = input.get_u32_le();
+            let mut face = Vec::with_capacity(face_vertex_count as usize);
+            for _ in 0..face_vertex_count {
+                face.push(input.get_u32_le());
+            }
+            faces.push(face);
+        }
+
+        Ok(Self {
+            name,
+            flags,
+            vertex_count,
+            face_count,
+            bounding_radius,
+            scale_factor,
+            vertices,
+            faces,
+        })
+    }
+}
+
+impl WldPolyhedronDefinition {
+    /// Fan-triangulates every face, returning indices into `vertices`.
+    pub fn triangles(&self) -> Vec<[u32; 3]> {
+        self.faces
+            .iter()
+            .filter(|face| face.len() >= 3)
+            .flat_map(|face| (1..face.len() - 1).map(|i| [face[0], face[i], face[i + 1]]))
+            .collect()
+    }
+}
+
+impl Debug for WldPolyhedronDefinitionFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WldPolyhedronDefinitionFlags")
+            .field("has_scale_factor", &self.has_scale_factor())
+            .finish()
+    }
+}
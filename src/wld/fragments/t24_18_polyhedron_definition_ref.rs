@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use bytes::Buf;
+use bytes::Bytes;
+
+use crate::Decoder;
+use crate::Settings;
+use crate::WldFragment;
+
+#[derive(Clone, Debug)]
+pub struct WldPolyhedronDefinitionRef {
+    pub name: Option<String>,
+    pub reference: u32,
+    pub flags: u32,
+    pub scale_factor: Option<f32>,
+}
+
+impl WldFragment for WldPolyhedronDefinitionRef {
+    const TYPE: u32 = 24;
+}
+
+impl Decoder<Settings> for WldPolyhedronDefinitionRef {
+    fn new(input: &mut Bytes, settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let name = settings.get_name();
+        let reference = input.get_u32_le();
+        let flags = input.get_u32_le();
+        let scale_factor = (flags & 0x01 != 0).then(|| input.get_f32_le());
+
+        Ok(Self {
+            name,
+            reference,
+            flags,
+            scale_factor,
+        })
+    }
+}
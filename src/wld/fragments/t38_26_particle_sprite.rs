@@ -10,9 +10,9 @@ use crate::WldFragment;
 #[derive(Clone, Debug)]
 pub struct WldParticleSprite {
     pub name: Option<String>,
-    flags: u32,
-    bitmap_ref: u32,
-    unk: u32,
+    pub flags: u32,
+    pub bitmap_ref: u32,
+    pub unk: u32,
 }
 
 impl WldFragment for WldParticleSprite {
@@ -10,8 +10,8 @@ use crate::WldFragment;
 #[derive(Clone, Debug)]
 pub struct WldParticleSpriteRef {
     pub name: Option<String>,
-    reference: u32,
-    unknown: u32,
+    pub reference: u32,
+    pub unknown: u32,
 }
 
 impl WldFragment for WldParticleSpriteRef {
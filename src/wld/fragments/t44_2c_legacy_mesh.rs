@@ -0,0 +1,265 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use bitbybit::bitfield;
+use bytes::Buf;
+use bytes::Bytes;
+
+use crate::Decoder;
+use crate::Settings;
+use crate::WldFragment;
+use crate::WldMesh;
+
+#[derive(Clone, Debug)]
+pub struct WldLegacyMesh {
+    pub name: Option<String>,
+
+    pub flags: WldLegacyMeshFlags,
+    pub vertex_count: u32,
+    pub uv_count: u32,
+    pub normal_count: u32,
+    pub color_count: u32,
+    pub face_count: u32,
+    pub mesh_op_count: u16,
+    pub unk1: u16,
+    pub vertex_bone_group_count: u32,
+    pub material_list_ref: u32,
+    pub animation_ref: u32,
+    pub unk_frag_ref: u32,
+    pub centre: (f32, f32, f32),
+    pub params1: u32,
+
+    pub position: Vec<[f32; 3]>,
+    pub uv: Vec<[f32; 2]>,
+    pub normal: Vec<[f32; 3]>,
+    pub color: Vec<[u8; 4]>,
+    pub faces: Vec<WldLegacyMeshFace>,
+    pub mesh_op: Vec<[u16; 6]>,
+    pub vertex_bone_group: Vec<(u16, u16, u16)>,
+    pub face_material_group: Vec<(u16, u16, u16)>,
+    pub vertex_material_group: Vec<(u16, u16, u16)>,
+}
+
+#[bitfield(u32)]
+pub struct WldLegacyMeshFlags {
+    #[bit(9, r)]
+    pub has_face_material_groups: bool, // 0x200
+    #[bit(11, r)]
+    pub has_vertex_material_groups: bool, // 0x800
+}
+
+#[derive(Clone, Debug)]
+pub struct WldLegacyMeshFace {
+    pub flags: u16,
+    pub data: [u16; 4],
+    pub vertices: [u16; 3],
+}
+
+impl WldFragment for WldLegacyMesh {
+    const TYPE: u32 = 44;
+}
+
+impl Decoder<Settings> for WldLegacyMesh {
+    fn new(input: &mut Bytes, settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let name = settings.get_name();
+        let flags = WldLegacyMeshFlags::new_with_raw_value(input.get_u32_le());
+        let vertex_count = input.get_u32_le();
+        let uv_count = input.get_u32_le();
+        let normal_count = input.get_u32_le();
+        let color_count = input.get_u32_le();
+        let face_count = input.get_u32_le();
+        let mesh_op_count = input.get_u16_le();
+        let unk1 = input.get_u16_le();
+        let vertex_bone_group_count = input.get_u32_le();
+        let material_list_ref = input.get_u32_le();
+        let animation_ref = input.get_u32_le();
+        let unk_frag_ref = input.get_u32_le();
+        let centre = (input.get_f32_le(), input.get_f32_le(), input.get_f32_le());
+        let params1 = input.get_u32_le();
+
+        // unlike 0x36 everything is stored as plain floats, no scale shift
+        let mut position = Vec::with_capacity(vertex_count as usize);
+        for _ in 0..vertex_count {
+            position.push([input.get_f32_le(), input.get_f32_le(), input.get_f32_le()]);
+        }
+
+        let mut uv = Vec::with_capacity(uv_count as usize);
+        for _ in 0..uv_count {
+            uv.push([input.get_f32_le(), input.get_f32_le()]);
+        }
+
+        let mut normal = Vec::with_capacity(normal_count as usize);
+        for _ in 0..normal_count {
+            normal.push([input.get_f32_le(), input.get_f32_le(), input.get_f32_le()]);
+        }
+
+        let mut color = Vec::with_capacity(color_count as usize);
+        for _ in 0..color_count {
+            color.push([
+                input.get_u8(),
+                input.get_u8(),
+                input.get_u8(),
+                input.get_u8(),
+            ]);
+        }
+
+        let mut faces = Vec::with_capacity(face_count as usize);
+        for _ in 0..face_count {
+            let flags = input.get_u16_le();
+            let data = [
+                input.get_u16_le(),
+                input.get_u16_le(),
+                input.get_u16_le(),
+                input.get_u16_le(),
+            ];
+            let vertices = [input.get_u16_le(), input.get_u16_le(), input.get_u16_le()];
+            faces.push(WldLegacyMeshFace {
+                flags,
+                data,
+                vertices,
+            });
+        }
+
+        let mut mesh_op = Vec::with_capacity(mesh_op_count as usize);
+        for _ in 0..mesh_op_count {
+            mesh_op.push([
+                input.get_u16_le(),
+                input.get_u16_le(),
+                input.get_u16_le(),
+                input.get_u16_le(),
+                input.get_u16_le(),
+                input.get_u16_le(),
+            ]);
+        }
+
+        // which vertices are assigned to each bone
+        let vertex_bone_group = groups(input, vertex_bone_group_count);
+
+        // which faces/triangles use a material
+        let face_material_group = if flags.has_face_material_groups() {
+            let count = input.get_u32_le();
+            groups(input, count)
+        } else {
+            Vec::new()
+        };
+
+        // which vertices use a material
+        let vertex_material_group = if flags.has_vertex_material_groups() {
+            let count = input.get_u32_le();
+            groups(input, count)
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            name,
+            flags,
+            vertex_count,
+            uv_count,
+            normal_count,
+            color_count,
+            face_count,
+            mesh_op_count,
+            unk1,
+            vertex_bone_group_count,
+            material_list_ref,
+            animation_ref,
+            unk_frag_ref,
+            centre,
+            params1,
+            position,
+            uv,
+            normal,
+            color,
+            faces,
+            mesh_op,
+            vertex_bone_group,
+            face_material_group,
+            vertex_material_group,
+        })
+    }
+}
+
+fn groups(input: &mut Bytes, count: u32) -> Vec<(u16, u16, u16)> {
+    let mut result = Vec::with_capacity(count as usize);
+    let mut idx = 0;
+    for _ in 0..count {
+        let count = input.get_u16_le();
+        let target = input.get_u16_le();
+        result.push((idx, count, target));
+        idx += count;
+    }
+    result
+}
+
+impl WldLegacyMesh {
+    /// Converts to the 0x36 representation so both formats can be consumed the same way.
+    /// Mesh ops have a different layout and are not carried over.
+    pub fn to_mesh(&self) -> WldMesh {
+        let face_material_group = if self.face_material_group.is_empty() {
+            vec![(0, self.faces.len() as u16, 0)]
+        } else {
+            self.face_material_group.clone()
+        };
+
+        WldMesh {
+            name: self.name.clone(),
+            flags: self.flags.raw_value(),
+            animation_ref: self.animation_ref,
+            centre: self.centre,
+            color_count: self.color.len() as u16,
+            scale: 1.0,
+            face_material_group_count: face_material_group.len() as u16,
+            material_list_ref: self.material_list_ref,
+            max_distance: 0.0,
+            max: (0.0, 0.0, 0.0),
+            mesh_op_count: 0,
+            min: (0.0, 0.0, 0.0),
+            normal_count: self.normal.len() as u16,
+            params2: (0, 0, 0),
+            triangle_count: self.faces.len() as u16,
+            unk1_frag_ref: self.unk_frag_ref,
+            unk2_frag_ref: 0,
+            uv_count: self.uv.len() as u16,
+            vertex_count: self.position.len() as u16,
+            vertex_bone_group_count: self.vertex_bone_group.len() as u16,
+            vertex_material_group_count: self.vertex_material_group.len() as u16,
+            color: self.color.clone(),
+            mesh_op: Vec::new(),
+            normal: self.normal.clone(),
+            position: self.position.clone(),
+            triangle: self
+                .faces
+                .iter()
+                .map(|face| {
+                    [
+                        face.flags,
+                        face.vertices[0],
+                        face.vertices[1],
+                        face.vertices[2],
+                    ]
+                })
+                .collect(),
+            uv: self.uv.clone(),
+            face_material_group,
+            vertex_bone_group: self.vertex_bone_group.clone(),
+            vertex_material_group: self.vertex_material_group.clone(),
+        }
+    }
+}
+
+impl Debug for WldLegacyMeshFlags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WldLegacyMeshFlags")
+            .field("has_face_material_groups", &self.has_face_material_groups())
+            .field(
+                "has_vertex_material_groups",
+                &self.has_vertex_material_groups(),
+            )
+            .finish()
+    }
+}
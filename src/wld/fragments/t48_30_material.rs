@@ -1,5 +1,11 @@
+use std::fmt::Debug;
+use std::fmt::Formatter;
 use std::sync::Arc;
 
+use arbitrary_int::u31;
+use arbitrary_int::u4;
+use bitbybit::bitenum;
+use bitbybit::bitfield;
 use bytes::Buf;
 use bytes::Bytes;
 
@@ -11,7 +17,7 @@ use crate::WldFragment;
 pub struct WldMaterial {
     pub name: Option<String>,
     pub flags: u32,
-    pub render_method: u32,
+    pub render_method: WldRenderMethod,
     pub rgb_pen: u32,
     pub brightness: f32,
     pub scaled_ambient: f32,
@@ -19,6 +25,73 @@ pub struct WldMaterial {
     pub pairs: Option<(u32, u32)>,
 }
 
+/// When `user_defined` is set, which is the case for every material the client ships, the
+/// remaining bits are a shader id rather than a draw style, lighting, fill and texture mode.
+#[bitfield(u32)]
+pub struct WldRenderMethod {
+    #[bits(0..=1, r)]
+    pub draw_style: WldDrawStyle,
+    #[bits(2..=4, r)]
+    pub lighting: Option<WldLighting>,
+    #[bits(5..=6, r)]
+    pub fill_mode: Option<WldFillMode>,
+    /// 0 when untextured.
+    #[bits(7..=10, r)]
+    pub texture_mode: u4,
+    #[bits(0..=30, r)]
+    pub user_defined_id: u31,
+    #[bit(31, r)]
+    pub user_defined: bool, // 0x80000000
+}
+
+#[bitenum(u2, exhaustive: true)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum WldDrawStyle {
+    Transparent = 0,
+    Points = 1,
+    Wireframe = 2,
+    Solid = 3,
+}
+
+#[bitenum(u3, exhaustive: false)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum WldLighting {
+    ZeroIntensity = 0,
+    Constant = 1,
+    Unknown = 2,
+    Ambient = 3,
+    ScaledAmbient = 4,
+}
+
+#[bitenum(u2, exhaustive: false)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum WldFillMode {
+    Constant = 0,
+    Unknown = 1,
+    Gouraud = 2,
+}
+
+impl WldRenderMethod {
+    pub fn is_transparent(&self) -> bool {
+        !self.user_defined() && self.draw_style() == WldDrawStyle::Transparent
+    }
+}
+
+impl Debug for WldRenderMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut x = f.debug_struct("WldRenderMethod");
+        if self.user_defined() {
+            x.field("user_defined_id", &format!("{:#x}", self.user_defined_id()));
+        } else {
+            x.field("draw_style", &self.draw_style());
+            x.field("lighting", &self.lighting());
+            x.field("fill_mode", &self.fill_mode());
+            x.field("texture_mode", &self.texture_mode());
+        }
+        x.finish()
+    }
+}
+
 impl WldFragment for WldMaterial {
     const TYPE: u32 = 48;
 }
@@ -31,7 +104,7 @@ impl Decoder<Settings> for WldMaterial {
         let name = settings.get_name();
 
         let flags = input.get_u32_le();
-        let render_method = input.get_u32_le() & 0x7FFFFFFF;
+        let render_method = WldRenderMethod::new_with_raw_value(input.get_u32_le());
         let rgb_pen = input.get_u32_le();
         let brightness = input.get_f32_le();
         let scaled_ambient = input.get_f32_le();
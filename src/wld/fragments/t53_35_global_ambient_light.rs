@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use bytes::Buf;
+use bytes::Bytes;
+
+use crate::Decoder;
+use crate::Settings;
+use crate::WldFragment;
+
+/// Usually the first fragment of a zone WLD. It has no name.
+#[derive(Clone, Debug)]
+pub struct WldGlobalAmbientLight {
+    pub name: Option<String>,
+    /// RGBA
+    pub color: [u8; 4],
+}
+
+impl WldFragment for WldGlobalAmbientLight {
+    const TYPE: u32 = 53;
+}
+
+impl Decoder<Settings> for WldGlobalAmbientLight {
+    fn new(input: &mut Bytes, settings: Arc<Settings>) -> Result<Self, crate::EQFilesError>
+    where
+        Self: Sized,
+    {
+        let name = settings.get_name();
+        // stored as BGRA
+        let (b, g, r, a) = (
+            input.get_u8(),
+            input.get_u8(),
+            input.get_u8(),
+            input.get_u8(),
+        );
+
+        Ok(Self {
+            name,
+            color: [r, g, b, a],
+        })
+    }
+}
@@ -1,9 +1,9 @@
-use std::collections::BTreeMap;
 use std::sync::Arc;
-use tracing::info;
 
 use bytes::Buf;
 use bytes::Bytes;
+use glam::Mat4;
+use glam::Vec3;
 
 use crate::Decoder;
 use crate::Settings;
@@ -228,6 +228,62 @@ impl Decoder<Settings> for WldMesh {
     }
 }
 
+/// Vertex data of a [WldMesh] after bone transforms were applied.
+#[derive(Clone, Debug)]
+pub struct WldSkinnedMesh {
+    pub position: Vec<[f32; 3]>,
+    pub normal: Vec<[f32; 3]>,
+}
+
+impl WldMesh {
+    /// `triangle[..][0]` flag for faces players walk through.
+    pub const PASSABLE: u16 = 0x10;
+
+    /// Triangles grouped by `face_material_group`, each with the index of its material in the
+    /// 0x31 material list. A mesh without groups yields all its triangles without a material.
+    pub fn material_triangles(&self) -> impl Iterator<Item = (Option<usize>, &[[u16; 4]])> {
+        let ungrouped = self
+            .face_material_group
+            .is_empty()
+            .then_some((None, self.triangle.as_slice()));
+        let grouped = self
+            .face_material_group
+            .iter()
+            .map(|(start, count, material)| {
+                let start = (*start as usize).min(self.triangle.len());
+                let end = (start + *count as usize).min(self.triangle.len());
+                (Some(*material as usize), &self.triangle[start..end])
+            });
+        ungrouped.into_iter().chain(grouped)
+    }
+
+    /// Deforms the mesh with `pose`, one world space matrix per skeleton dag as returned by
+    /// `Skeleton::pose`. Each range in `vertex_bone_group` is moved by the matrix of its dag;
+    /// vertices not covered by any group, or whose dag is out of range, are left as they are.
+    pub fn skin(&self, pose: &[Mat4]) -> WldSkinnedMesh {
+        let mut position = self.position.clone();
+        let mut normal = self.normal.clone();
+
+        for (start, count, dag) in &self.vertex_bone_group {
+            let Some(matrix) = pose.get(*dag as usize) else {
+                continue;
+            };
+            let range = *start as usize..(*start + *count) as usize;
+            for vertex in position.get_mut(range.clone()).unwrap_or_default() {
+                *vertex = matrix.transform_point3(Vec3::from(*vertex)).to_array();
+            }
+            for vertex in normal.get_mut(range).unwrap_or_default() {
+                *vertex = matrix
+                    .transform_vector3(Vec3::from(*vertex))
+                    .normalize_or_zero()
+                    .to_array();
+            }
+        }
+
+        WldSkinnedMesh { position, normal }
+    }
+}
+
 // copied from https://github.com/bryab/libeq/blob/master/crates/libeq_wld/src/parser/fragments/mesh.rs#L457
 #[derive(Clone, Debug)]
 pub struct MeshOp {
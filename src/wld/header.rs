@@ -2,7 +2,6 @@ use std::sync::Arc;
 
 use bytes::Buf;
 use bytes::Bytes;
-use tracing::info;
 
 use crate::Decoder;
 use crate::EQFilesError;
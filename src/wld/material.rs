@@ -0,0 +1,126 @@
+use super::WldFile;
+use crate::WldDrawStyle;
+use crate::WldMaterial;
+use crate::WldMaterialList;
+use crate::WldRenderMethod;
+use crate::WldTextureBitmapInfo;
+use crate::WldTextureBitmapInfoRef;
+use crate::WldTextureBitmapName;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    Opaque,
+    /// Fully transparent where the texture's first palette entry is used.
+    Masked,
+    Additive,
+    Transparent,
+    /// Not drawn at all, e.g. zone boundaries.
+    Invisible,
+}
+
+/// Everything needed to draw a [WldMaterial], with its texture chain followed.
+#[derive(Clone, Debug)]
+pub struct ResolvedMaterial {
+    pub name: Option<String>,
+    /// Texture filenames, one per animation frame, starting with the one shown first.
+    pub textures: Vec<String>,
+    /// Milliseconds each texture is shown for, if the material is animated.
+    pub frame_delay: Option<u32>,
+    pub blend_mode: BlendMode,
+    /// Only meaningful for [BlendMode::Transparent].
+    pub opacity: f32,
+    pub brightness: f32,
+    pub rgb_pen: u32,
+}
+
+impl ResolvedMaterial {
+    /// Texture filename shown `elapsed_ms` after the material appeared.
+    pub fn texture_at(&self, elapsed_ms: u32) -> Option<&str> {
+        let frame = match self.frame_delay {
+            Some(delay) if delay > 0 && !self.textures.is_empty() => {
+                (elapsed_ms / delay) as usize % self.textures.len()
+            }
+            _ => 0,
+        };
+        self.textures.get(frame).map(String::as_str)
+    }
+}
+
+impl WldFile {
+    /// Follows `material` -> 0x05 -> 0x04 -> 0x03 down to the texture filenames.
+    pub fn resolve_material(&self, material: &WldMaterial) -> ResolvedMaterial {
+        let info = self
+            .fragment_of_type::<WldTextureBitmapInfoRef>(material.texture_list_ref)
+            .and_then(|info_ref| {
+                self.fragment_of_type::<WldTextureBitmapInfo>(info_ref.texture_ref as u32)
+            });
+
+        let textures = info
+            .iter()
+            .flat_map(|info| info.frame_sequence())
+            .filter_map(|(_, name_ref)| self.fragment_of_type::<WldTextureBitmapName>(name_ref))
+            .filter_map(|name| name.textures.into_iter().next())
+            .collect::<Vec<_>>();
+        let frame_delay = info
+            .filter(|info| info.flags.animated() && textures.len() > 1)
+            .map(|info| info.sleep);
+
+        let (blend_mode, opacity) = blend_mode(material.render_method);
+
+        ResolvedMaterial {
+            name: material.name.clone(),
+            textures,
+            frame_delay,
+            blend_mode,
+            opacity,
+            brightness: material.brightness,
+            rgb_pen: material.rgb_pen,
+        }
+    }
+
+    /// Resolves every material of a 0x31 list, e.g. a mesh's `material_list_ref`, keeping
+    /// their order so they can be indexed by material group. A reference that doesn't lead
+    /// to a material gives an untextured opaque one.
+    pub fn resolve_material_list(&self, material_list_ref: u32) -> Vec<ResolvedMaterial> {
+        self.fragment_of_type::<WldMaterialList>(material_list_ref)
+            .map(|list| {
+                list.material_refs
+                    .iter()
+                    .map(|material_ref| {
+                        self.fragment_of_type::<WldMaterial>(*material_ref)
+                            .map(|material| self.resolve_material(&material))
+                            .unwrap_or_else(|| ResolvedMaterial {
+                                name: None,
+                                textures: Vec::new(),
+                                frame_delay: None,
+                                blend_mode: BlendMode::Opaque,
+                                opacity: 1.0,
+                                brightness: 0.0,
+                                rgb_pen: 0,
+                            })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn blend_mode(render_method: WldRenderMethod) -> (BlendMode, f32) {
+    if !render_method.user_defined() {
+        return match render_method.draw_style() {
+            WldDrawStyle::Transparent => (BlendMode::Invisible, 0.0),
+            _ => (BlendMode::Opaque, 1.0),
+        };
+    }
+
+    // shaders the client ships with
+    match render_method.user_defined_id().value() {
+        0x00 | 0x03 | 0x4B | 0x53 => (BlendMode::Invisible, 0.0),
+        0x05 | 0x0F => (BlendMode::Transparent, 0.5),
+        0x09 => (BlendMode::Transparent, 0.25),
+        0x0A => (BlendMode::Transparent, 0.75),
+        0x07 | 0x13 => (BlendMode::Masked, 1.0),
+        0x0B | 0x10 | 0x17 => (BlendMode::Additive, 1.0),
+        _ => (BlendMode::Opaque, 1.0),
+    }
+}
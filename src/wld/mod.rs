@@ -1,7 +1,9 @@
 pub(crate) mod fragments;
 mod header;
+pub(crate) mod material;
 mod names;
 mod raw_fragment;
+pub(crate) mod skeleton;
 
 use std::collections::BTreeMap;
 use std::sync::Arc;
@@ -11,7 +13,8 @@ use fragments::*;
 use header::WldHeader;
 use names::WldNames;
 use raw_fragment::WldRawFragment;
-use tracing::info;
+use skeleton::Skeleton;
+use skeleton::SkeletonAnimation;
 
 use crate::utils::*;
 use crate::Decoder;
@@ -51,8 +54,8 @@ impl Decoder<EmptySettings> for WldFile {
         // info!("fragments by index: {}", fragments_by_index.len());
 
         let fragments_by_name = fragments_by_index
-            .iter()
-            .filter_map(|(_, v)| v.clone().name.clone().map(|n| (n, v.clone())))
+            .values()
+            .filter_map(|v| v.clone().name.clone().map(|n| (n, v.clone())))
             .collect();
 
         Ok(WldFile {
@@ -77,6 +80,18 @@ impl WldFile {
         T::new(&mut raw, self.base_settings.make_settings(fragment)).ok()
     }
 
+    /// Like [WldFile::fragment_by_index], but `None` instead of panicking when the fragment at
+    /// `index` is of another type, for references read from the file itself.
+    pub(crate) fn fragment_of_type<T>(&self, index: FragmentIndex) -> Option<T>
+    where
+        T: WldFragment,
+    {
+        self.fragments_by_index
+            .get(&index)
+            .filter(|fragment| fragment.fragment_type == T::TYPE)?;
+        self.fragment_by_index(index)
+    }
+
     pub fn fragment_by_name<T>(&self, name: String) -> Option<T>
     where
         T: WldFragment,
@@ -139,6 +154,26 @@ impl WldFile {
             .collect()
     }
 
+    /// Every fragment of type `T` in file order. Unlike [WldFile::fragments_by_type], which
+    /// goes by name, this includes unnamed fragments and every fragment sharing a name.
+    pub fn fragments_in_file_order<T>(&self) -> Vec<T>
+    where
+        T: WldFragment,
+    {
+        self.fragments_by_index
+            .values()
+            .filter(|fragment| fragment.fragment_type == T::TYPE)
+            .filter_map(|fragment| {
+                let mut cont = fragment.contents.clone();
+                T::new(
+                    &mut cont,
+                    self.base_settings.make_settings(fragment.clone()),
+                )
+                .ok()
+            })
+            .collect()
+    }
+
     pub fn type_by_index(&self, index: u32) -> u32 {
         let fragment = self.fragments_by_index.get(&index).unwrap().clone();
         fragment.fragment_type
@@ -151,4 +186,42 @@ impl WldFile {
     pub fn models(&self) -> Vec<WldModel> {
         self.fragments_by_type(WldModel::TYPE)
     }
+
+    /// Every 0x36 mesh, which for a zone file are its region meshes.
+    pub fn meshes(&self) -> Vec<WldMesh> {
+        self.fragments_in_file_order::<WldMesh>()
+    }
+
+    pub fn object_locations(&self) -> Vec<WldObjectLocation> {
+        self.fragments_in_file_order::<WldObjectLocation>()
+    }
+
+    /// Meshes of a 0x14 model, following its 0x2D references.
+    pub fn model_meshes(&self, model: &WldModel) -> Vec<WldMesh> {
+        model
+            .fragments
+            .iter()
+            .filter_map(|index| self.fragment_of_type::<WldMeshRef>(*index))
+            .filter_map(|mesh_ref| self.fragment_of_type::<WldMesh>(mesh_ref.reference))
+            .collect()
+    }
+
+    /// The first 0x08 fragment, a 3D sprite definition (`3DSPRITEDEF`): a vertex list with
+    /// BSP nodes. Zone files use one for the camera, but it carries no view position.
+    pub fn default_sprite_def(&self) -> Option<WldCamera> {
+        self.fragments_in_file_order::<WldCamera>()
+            .into_iter()
+            .next()
+    }
+
+    pub fn global_ambient_light(&self) -> Option<WldGlobalAmbientLight> {
+        self.fragments_in_file_order::<WldGlobalAmbientLight>()
+            .into_iter()
+            .next()
+    }
+
+    /// Named animations that can be played on `skeleton`, including borrowed ones.
+    pub fn animations_for(&self, skeleton: &Skeleton) -> Vec<SkeletonAnimation> {
+        skeleton.animations(self)
+    }
 }
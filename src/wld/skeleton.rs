@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use glam::Mat4;
+
+use super::WldFile;
+use crate::WldSkeletonDag;
+use crate::WldSkeletonPieceTrack;
+use crate::WldSkeletonPieceTrackDef;
+use crate::WldSkeletonTrackSet;
+
+/// A 0x13 piece track together with the 0x12 frames it points to.
+#[derive(Clone, Debug)]
+pub struct SkeletonTrack {
+    pub track: WldSkeletonPieceTrack,
+    pub definition: WldSkeletonPieceTrackDef,
+}
+
+impl SkeletonTrack {
+    pub fn from_track(wld: &WldFile, track: WldSkeletonPieceTrack) -> Option<Self> {
+        let definition = wld.fragment_of_type(track.reference)?;
+        Some(Self { track, definition })
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.definition.frames.len()
+    }
+
+    /// Milliseconds each frame is shown for, if the track animates.
+    pub fn sleep(&self) -> Option<u32> {
+        self.track.sleep.filter(|sleep| *sleep > 0)
+    }
+
+    pub fn local_matrix(&self, time_ms: u32) -> Mat4 {
+        let frames = &self.definition.frames;
+        let count = frames.len();
+        if count == 0 {
+            return Mat4::IDENTITY;
+        }
+        let Some(sleep) = self.sleep().filter(|_| count > 1) else {
+            return frames[0].local_matrix();
+        };
+
+        let position = time_ms as f32 / sleep as f32;
+        let step = position.floor() as usize;
+        let fraction = position.fract();
+        let (current, next) = if self.track.flags.reverse() {
+            let current = count - 1 - step % count;
+            (current, (current + count - 1) % count)
+        } else {
+            let current = step % count;
+            (current, (current + 1) % count)
+        };
+
+        if self.track.flags.interpolate() {
+            frames[current].lerp(&frames[next], fraction).local_matrix()
+        } else {
+            frames[current].local_matrix()
+        }
+    }
+}
+
+/// A set of tracks keyed by dag index. Dags without an entry keep their rest track.
+#[derive(Clone, Debug, Default)]
+pub struct SkeletonAnimation {
+    /// Animation code such as `C01` or `L02`.
+    pub code: String,
+    /// Model code the tracks were taken from, which differs from the skeleton's own when the
+    /// animation is borrowed from another race.
+    pub model: String,
+    pub frame_count: usize,
+    pub duration_ms: u32,
+    pub tracks: BTreeMap<usize, SkeletonTrack>,
+}
+
+impl SkeletonAnimation {
+    fn insert(&mut self, index: usize, track: SkeletonTrack) {
+        self.frame_count = self.frame_count.max(track.frame_count());
+        self.duration_ms = self
+            .duration_ms
+            .max(track.frame_count() as u32 * track.sleep().unwrap_or(0));
+        self.tracks.insert(index, track);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Skeleton {
+    pub name: Option<String>,
+    pub dags: Vec<WldSkeletonDag>,
+    pub tracks: Vec<Option<SkeletonTrack>>,
+}
+
+impl Skeleton {
+    pub fn new(wld: &WldFile, track_set: &WldSkeletonTrackSet) -> Self {
+        let tracks = track_set
+            .dags
+            .iter()
+            .map(|dag| {
+                wld.fragment_of_type(dag.track_ref)
+                    .and_then(|track| SkeletonTrack::from_track(wld, track))
+            })
+            .collect();
+
+        let mut skeleton = Self {
+            name: track_set.name.clone(),
+            dags: track_set.dags.clone(),
+            tracks,
+        };
+        skeleton.fill_model_matrices();
+        skeleton
+    }
+
+    /// Model code the skeleton's tracks are prefixed with, e.g. `HUM` for `HUM_HS_DEF`.
+    pub fn model_code(&self) -> Option<&str> {
+        self.name.as_deref()?.split('_').next()
+    }
+
+    /// Rest track names with the model code removed, which is what animation tracks share.
+    fn bone_suffixes(&self) -> BTreeMap<String, usize> {
+        let Some(model) = self.model_code() else {
+            return BTreeMap::new();
+        };
+        self.tracks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, track)| {
+                let name = track.as_ref()?.track.name.as_ref()?;
+                Some((name.strip_prefix(model)?.to_string(), index))
+            })
+            .collect()
+    }
+
+    /// Animation tracks are named `<code><model><bone>`, e.g. `C01HUMPE_TRACK`. Tracks of other
+    /// models with the same bone names are grouped too, as races share animations this way.
+    pub(crate) fn animations(&self, wld: &WldFile) -> Vec<SkeletonAnimation> {
+        let Some(own_model) = self.model_code() else {
+            return Vec::new();
+        };
+        let bones = self.bone_suffixes();
+
+        let mut grouped: BTreeMap<(String, String), SkeletonAnimation> = BTreeMap::new();
+        for track in wld.fragments_in_file_order::<WldSkeletonPieceTrack>() {
+            let Some(name) = track.name.clone() else {
+                continue;
+            };
+            let bytes = name.as_bytes();
+            if !name.is_ascii()
+                || bytes.len() <= 3 + own_model.len()
+                || !bytes[0].is_ascii_uppercase()
+                || !bytes[1].is_ascii_digit()
+                || !bytes[2].is_ascii_digit()
+            {
+                continue;
+            }
+            let (code, rest) = name.split_at(3);
+            let (model, bone) = rest.split_at(own_model.len());
+            let Some(index) = bones.get(bone) else {
+                continue;
+            };
+            let Some(track) = SkeletonTrack::from_track(wld, track) else {
+                continue;
+            };
+            grouped
+                .entry((code.to_string(), model.to_string()))
+                .or_insert_with(|| SkeletonAnimation {
+                    code: code.to_string(),
+                    model: model.to_string(),
+                    ..Default::default()
+                })
+                .insert(*index, track);
+        }
+
+        let (mut own, borrowed): (Vec<_>, Vec<_>) = grouped
+            .into_values()
+            .partition(|animation| animation.model == own_model);
+        for animation in borrowed {
+            if !own.iter().any(|other| other.code == animation.code) {
+                own.push(animation);
+            }
+        }
+        own
+    }
+
+    /// Dag indices ordered so every parent comes before its children.
+    pub fn hierarchy_order(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.dags.len());
+        let mut visited = vec![false; self.dags.len()];
+        let mut pending: Vec<usize> = (0..self.dags.len())
+            .rev()
+            .filter(|index| self.dags[*index].parent.is_none())
+            .collect();
+        while let Some(index) = pending.pop() {
+            // sub dags come from the file, skip any that are out of range or seen already
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            order.push(index);
+            for sub in self.dags[index].sub_dags.iter().rev() {
+                if (*sub as usize) < self.dags.len() {
+                    pending.push(*sub as usize);
+                }
+            }
+        }
+        order
+    }
+
+    pub fn rest_pose(&self) -> Vec<Mat4> {
+        self.pose(&SkeletonAnimation::default(), 0)
+    }
+
+    /// World space matrix of every dag at `time_ms` into `animation`.
+    pub fn pose(&self, animation: &SkeletonAnimation, time_ms: u32) -> Vec<Mat4> {
+        let mut result = vec![Mat4::IDENTITY; self.dags.len()];
+        for index in self.hierarchy_order() {
+            let local = animation
+                .tracks
+                .get(&index)
+                .or(self.tracks[index].as_ref())
+                .map(|track| track.local_matrix(time_ms))
+                .unwrap_or(Mat4::IDENTITY);
+            result[index] = match self.dags[index].parent {
+                Some(parent) => result[parent as usize] * local,
+                None => local,
+            };
+        }
+        result
+    }
+
+    /// Stores the world space matrix of every rest track frame in its `model_matrix`.
+    fn fill_model_matrices(&mut self) {
+        let mut world_frames: Vec<Vec<Mat4>> = vec![Vec::new(); self.dags.len()];
+        for index in self.hierarchy_order() {
+            let parent_frames = match self.dags[index].parent {
+                Some(parent) => world_frames[parent as usize].clone(),
+                None => Vec::new(),
+            };
+            let parent_at = |i: usize| {
+                parent_frames
+                    .get(i)
+                    .or(parent_frames.last())
+                    .copied()
+                    .unwrap_or(Mat4::IDENTITY)
+            };
+
+            world_frames[index] = match self.tracks[index].as_mut() {
+                Some(track) => track
+                    .definition
+                    .frames
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(i, frame)| {
+                        frame.model_matrix = parent_at(i) * frame.local_matrix();
+                        frame.model_matrix
+                    })
+                    .collect(),
+                None => parent_frames.clone(),
+            };
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+use crate::text::decode;
+use crate::Decoder;
+use crate::EQFilesError;
+use crate::PackFile;
+use crate::WldFile;
+use crate::EMPTY_SETTINGS;
+
+/// The character archives a zone uses (`<zone>_chr.txt`), e.g. `globalelf_chr`.
+#[derive(Clone, Debug, Default)]
+pub struct CharacterImports {
+    pub archives: Vec<CharacterImport>,
+}
+
+/// One line of a `_chr.txt`, names kept in the case the file uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CharacterImport {
+    /// Archive name without extension.
+    pub archive: String,
+    /// Name of the WLD inside the archive, when it differs from the archive's.
+    pub wld: Option<String>,
+}
+
+impl CharacterImport {
+    /// `wld`, or the archive's own name, with a `.wld` extension.
+    pub fn wld_filename(&self) -> String {
+        let name = self.wld.as_deref().unwrap_or(&self.archive);
+        format!("{}.wld", strip_extension(name, ".wld"))
+    }
+}
+
+impl CharacterImports {
+    /// A count on the first line, then one archive per line, optionally followed by a comma
+    /// and the name of the WLD inside it.
+    pub fn parse(bytes: &[u8]) -> Result<Self, EQFilesError> {
+        let text = decode(bytes);
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let count = lines
+            .next()
+            .and_then(|line| line.parse::<usize>().ok())
+            .ok_or(EQFilesError::InvalidTextLine(1, "missing archive count"))?;
+        let archives = lines
+            .take(count)
+            .map(|line| {
+                let mut fields = line.split(',').map(str::trim);
+                let archive = fields.next().unwrap_or(line);
+                CharacterImport {
+                    archive: strip_extension(archive, ".s3d").to_string(),
+                    wld: fields
+                        .next()
+                        .filter(|wld| !wld.is_empty())
+                        .map(String::from),
+                }
+            })
+            .collect();
+        Ok(Self { archives })
+    }
+
+    /// Opens every archive in the game `directory` and parses the WLD named on its line, or
+    /// the one of the same name as the archive, e.g. `globalelf_chr.wld` in
+    /// `globalelf_chr.s3d`.
+    pub fn load(&self, directory: &Path) -> Result<Vec<(String, WldFile)>, EQFilesError> {
+        self.archives
+            .iter()
+            .map(|import| {
+                let path = directory.join(format!("{}.s3d", import.archive));
+                let mut contents = Bytes::from(std::fs::read(&path)?);
+                let pack = PackFile::new(&mut contents, Arc::new(path))?;
+                let wld_name = import.wld_filename();
+                let mut wld = pack
+                    .get(&wld_name)
+                    .ok_or(EQFilesError::MissingFile(wld_name))?;
+                Ok((
+                    import.archive.clone(),
+                    WldFile::new(&mut wld, EMPTY_SETTINGS.clone())?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// `name` without `extension`, which is matched ignoring case.
+fn strip_extension<'a>(name: &'a str, extension: &str) -> &'a str {
+    match name.len().checked_sub(extension.len()) {
+        Some(split)
+            if name.is_char_boundary(split) && name[split..].eq_ignore_ascii_case(extension) =>
+        {
+            &name[..split]
+        }
+        _ => name,
+    }
+}
@@ -0,0 +1,34 @@
+use bytes::Buf;
+use bytes::Bytes;
+
+use crate::EQFilesError;
+
+/// Baked per-vertex lighting of a placed object (`.lit`), one colour per vertex of its
+/// model in order.
+#[derive(Clone, Debug, Default)]
+pub struct LitFile {
+    /// RGBA, stored as BGRA.
+    pub colors: Vec<[u8; 4]>,
+}
+
+impl LitFile {
+    pub fn parse(bytes: &mut Bytes) -> Result<Self, EQFilesError> {
+        if bytes.remaining() < 4 {
+            return Err(EQFilesError::Truncated("lit file header"));
+        }
+        let count = bytes.get_u32_le() as usize;
+        if count
+            .checked_mul(4)
+            .is_none_or(|length| length > bytes.remaining())
+        {
+            return Err(EQFilesError::InvalidLitFile(count));
+        }
+        let colors = (0..count)
+            .map(|_| {
+                let [b, g, r, a] = bytes.get_u32_le().to_le_bytes();
+                [r, g, b, a]
+            })
+            .collect();
+        Ok(Self { colors })
+    }
+}
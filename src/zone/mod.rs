@@ -0,0 +1,6 @@
+mod chr;
+mod lit;
+
+pub use self::chr::CharacterImport;
+pub use self::chr::CharacterImports;
+pub use self::lit::LitFile;